@@ -1,18 +1,32 @@
 use crate::token;
+use std::fmt;
 
 pub enum Statements {
     Let(LetStatement),
     Return(ReturnStatement),
     Expression(ExpressionStatement),
+    While(WhileStatement),
+    For(ForStatement),
+    Break(BreakStatement),
+    Continue(ContinueStatement),
 }
 
 pub enum Expressions {
     Identifier(Identifier),
     IntegerLiteral(IntegerLiteral),
+    FloatLiteral(FloatLiteral),
+    StringLiteral(StringLiteral),
     Prefix(PrefixExpression),
     Infix(InfixExpression),
+    Logical(LogicalExpression),
+    Assign(AssignExpression),
     Boolean(Boolean),
     If(IfStatement),
+    Function(FunctionLiteral),
+    Call(CallExpression),
+    Array(ArrayLiteral),
+    Index(IndexExpression),
+    Hash(HashLiteral),
 }
 
 pub struct Program {
@@ -32,7 +46,30 @@ pub struct LetStatement {
 
 pub struct ReturnStatement {
     pub token: token::Token,
-    // pub return_value : Expressions
+    pub return_value: Option<Expressions>,
+}
+
+pub struct WhileStatement {
+    pub token: token::Token,
+    pub condition: Expressions,
+    pub body: BlockStatement,
+}
+
+/// `for (item in iterable)` or `for (key, value in iterable)`. The loop
+/// variables are bound in a scope of their own for each iteration.
+pub struct ForStatement {
+    pub token: token::Token,
+    pub variables: Vec<Identifier>,
+    pub iterable: Expressions,
+    pub body: BlockStatement,
+}
+
+pub struct BreakStatement {
+    pub token: token::Token,
+}
+
+pub struct ContinueStatement {
+    pub token: token::Token,
 }
 
 pub struct Boolean {
@@ -45,6 +82,16 @@ pub struct IntegerLiteral {
     pub value: i64,
 }
 
+pub struct FloatLiteral {
+    pub token: token::Token,
+    pub value: f64,
+}
+
+pub struct StringLiteral {
+    pub token: token::Token,
+    pub value: String,
+}
+
 pub struct ExpressionStatement {
     pub token: token::Token,
     pub expression: Expressions,
@@ -63,6 +110,15 @@ pub struct InfixExpression {
     pub right: Box<Expressions>,
 }
 
+/// `&&` or `||`. Unlike an `InfixExpression`, `right` is only evaluated when
+/// `left` does not already decide the result.
+pub struct LogicalExpression {
+    pub token: token::Token,
+    pub left: Box<Expressions>,
+    pub operator: String,
+    pub right: Box<Expressions>,
+}
+
 pub struct IfStatement {
     pub token: token::Token,
     pub condition: Box<Expressions>,
@@ -70,6 +126,43 @@ pub struct IfStatement {
     pub alternative: Option<BlockStatement>,
 }
 
+pub struct FunctionLiteral {
+    pub token: token::Token,
+    pub parameters: Vec<Identifier>,
+    pub body: BlockStatement,
+}
+
+pub struct CallExpression {
+    pub token: token::Token,
+    pub function: Box<Expressions>,
+    pub arguments: Vec<Expressions>,
+}
+
+pub struct ArrayLiteral {
+    pub token: token::Token,
+    pub elements: Vec<Expressions>,
+}
+
+pub struct HashLiteral {
+    pub token: token::Token,
+    pub pairs: Vec<(Expressions, Expressions)>,
+}
+
+/// `target = value` or a compound form such as `target += value`. The parser
+/// only builds this for identifier and index targets.
+pub struct AssignExpression {
+    pub token: token::Token,
+    pub target: Box<Expressions>,
+    pub operator: String,
+    pub value: Box<Expressions>,
+}
+
+pub struct IndexExpression {
+    pub token: token::Token,
+    pub left: Box<Expressions>,
+    pub index: Box<Expressions>,
+}
+
 pub struct BlockStatement {
     pub token: token::Token,
     pub statements: Vec<Statements>,
@@ -99,51 +192,29 @@ impl PrefixExpression {
     }
 }
 
-impl IfStatement {
-    fn to_string(&self) -> String {
-        let mut output = format!(
-            "if {} {}",
-            self.condition.to_string(),
-            self.consequence.to_string()
-        );
-
-        if let Some(alternative) = &self.alternative {
-            output += format!("else {}", alternative.to_string()).as_str();
-        }
-        output
-    }
-}
-
-impl BlockStatement {
-    fn to_string(&self) -> String {
-        let mut output = String::new();
-
-        for statement in &self.statements {
-            output += statement.to_string().clone().as_str();
-        }
-
-        output
-    }
-}
 impl Statements {
     pub fn token_literal(&self) -> String {
         match self {
             Statements::Let(i) => i.token.literal.clone(),
             Statements::Return(i) => i.token.literal.clone(),
             Statements::Expression(i) => i.expression.to_string(),
+            Statements::While(i) => i.token.literal.clone(),
+            Statements::For(i) => i.token.literal.clone(),
+            Statements::Break(i) => i.token.literal.clone(),
+            Statements::Continue(i) => i.token.literal.clone(),
         }
     }
 
-    pub fn to_string(&self) -> String {
+    /// The first token of the statement.
+    pub fn token(&self) -> &token::Token {
         match self {
-            Statements::Let(s) => format!(
-                "{} {} = {};",
-                s.token_literal(),
-                s.name.token_literal(),
-                s.value.to_string()
-            ),
-            Statements::Return(s) => format!("{};", s.token.literal),
-            Statements::Expression(s) => format!("{}", s.expression.to_string()),
+            Statements::Let(s) => &s.token,
+            Statements::Return(s) => &s.token,
+            Statements::Expression(s) => &s.token,
+            Statements::While(s) => &s.token,
+            Statements::For(s) => &s.token,
+            Statements::Break(s) => &s.token,
+            Statements::Continue(s) => &s.token,
         }
     }
 
@@ -156,87 +227,329 @@ impl Statements {
 
     pub fn let_statement(&self) -> &LetStatement {
         match self {
-            Statements::Let(l) => &l,
+            Statements::Let(l) => l,
             _ => panic!("Not a let statement."),
         }
     }
-}
 
-impl Expressions {
-    fn to_string(&self) -> String {
-        match &self {
-            Expressions::Identifier(v) => v.value.clone(),
-            Expressions::IntegerLiteral(v) => v.value.to_string().clone(),
-            Expressions::Boolean(v) => v.value.to_string().clone(),
-            Expressions::Prefix(v) => format!("({}{})", v.operator, v.right.to_string()),
-            Expressions::Infix(v) => format!(
-                "({} {} {})",
-                v.left.to_string(),
-                v.operator,
-                v.right.to_string()
-            ),
-            Expressions::If(i) => i.to_string(),
+    pub fn while_statement(&self) -> &WhileStatement {
+        match self {
+            Statements::While(w) => w,
+            _ => panic!("Not a while statement."),
+        }
+    }
+
+    pub fn for_statement(&self) -> &ForStatement {
+        match self {
+            Statements::For(f) => f,
+            _ => panic!("Not a for statement."),
+        }
+    }
+
+    pub fn return_statement(&self) -> &ReturnStatement {
+        match self {
+            Statements::Return(r) => r,
+            _ => panic!("Not a return statement."),
         }
     }
+}
 
+impl Expressions {
     pub fn identifier(&self) -> &Identifier {
         match self {
-            Expressions::Identifier(i) => &i,
+            Expressions::Identifier(i) => i,
             _ => panic!("Not an identifier expression."),
         }
     }
 
     pub fn integer_literal(&self) -> &IntegerLiteral {
         match self {
-            Expressions::IntegerLiteral(i) => &i,
+            Expressions::IntegerLiteral(i) => i,
             _ => panic!("Not an integer literal expression."),
         }
     }
 
+    pub fn float_literal(&self) -> &FloatLiteral {
+        match self {
+            Expressions::FloatLiteral(f) => f,
+            _ => panic!("Not a float literal expression."),
+        }
+    }
+
+    pub fn string_literal(&self) -> &StringLiteral {
+        match self {
+            Expressions::StringLiteral(s) => s,
+            _ => panic!("Not a string literal expression."),
+        }
+    }
+
     pub fn prefix(&self) -> &PrefixExpression {
         match self {
-            Expressions::Prefix(p) => &p,
+            Expressions::Prefix(p) => p,
             _ => panic!("Not an prefix expression."),
         }
     }
 
     pub fn infix(&self) -> &InfixExpression {
         match self {
-            Expressions::Infix(i) => &i,
+            Expressions::Infix(i) => i,
             _ => panic!("Not an infix expression."),
         }
     }
 
+    pub fn logical(&self) -> &LogicalExpression {
+        match self {
+            Expressions::Logical(l) => l,
+            _ => panic!("Not a logical expression."),
+        }
+    }
+
+    pub fn assign(&self) -> &AssignExpression {
+        match self {
+            Expressions::Assign(a) => a,
+            _ => panic!("Not an assign expression."),
+        }
+    }
+
     pub fn boolean(&self) -> &Boolean {
         match self {
-            Expressions::Boolean(b) => &b,
+            Expressions::Boolean(b) => b,
             _ => panic!("Not an infix expression."),
         }
     }
 
     pub fn if_statement(&self) -> &IfStatement {
         match self {
-            Expressions::If(i) => &i,
+            Expressions::If(i) => i,
             _ => panic!("Not an if statement"),
         }
     }
+
+    pub fn function(&self) -> &FunctionLiteral {
+        match self {
+            Expressions::Function(f) => f,
+            _ => panic!("Not a function literal."),
+        }
+    }
+
+    pub fn call(&self) -> &CallExpression {
+        match self {
+            Expressions::Call(c) => c,
+            _ => panic!("Not a call expression."),
+        }
+    }
+
+    pub fn array(&self) -> &ArrayLiteral {
+        match self {
+            Expressions::Array(a) => a,
+            _ => panic!("Not an array literal."),
+        }
+    }
+
+    pub fn hash(&self) -> &HashLiteral {
+        match self {
+            Expressions::Hash(h) => h,
+            _ => panic!("Not a hash literal."),
+        }
+    }
+
+    pub fn index(&self) -> &IndexExpression {
+        match self {
+            Expressions::Index(i) => i,
+            _ => panic!("Not an index expression."),
+        }
+    }
 }
 
 impl Program {
-    fn token_literal(&self) -> String {
+    pub fn token_literal(&self) -> String {
         if self.statements.len() > 1 {
             return self.statements[0].token_literal();
         }
         "".to_string()
     }
+}
+
+impl fmt::Display for Program {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_statements(f, &self.statements)
+    }
+}
+
+// Writes `statements` separated by spaces. Every expression statement but the
+// last ends in `;` so that the statement after it cannot continue it.
+fn write_statements(f: &mut fmt::Formatter, statements: &[Statements]) -> fmt::Result {
+    for (i, statement) in statements.iter().enumerate() {
+        if i > 0 {
+            write!(f, " ")?;
+        }
+        write!(f, "{}", statement)?;
+
+        if let Statements::Expression(_) = statement {
+            if i + 1 < statements.len() {
+                write!(f, ";")?;
+            }
+        }
+    }
 
-    pub fn to_string(&self) -> String {
-        let mut output = String::new();
+    Ok(())
+}
 
-        for statement in &self.statements {
-            output.push_str(&statement.to_string().clone());
+// Writes a loop or `if` condition in the parentheses the grammar requires.
+// Operator expressions already print inside their own.
+fn write_condition(f: &mut fmt::Formatter, condition: &Expressions) -> fmt::Result {
+    match condition {
+        Expressions::Prefix(_)
+        | Expressions::Infix(_)
+        | Expressions::Logical(_)
+        | Expressions::Assign(_)
+        | Expressions::Index(_) => write!(f, "{}", condition),
+        _ => write!(f, "({})", condition),
+    }
+}
+
+impl fmt::Display for Statements {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Statements::Let(s) => write!(f, "{} {} = {};", s.token_literal(), s.name, s.value),
+            Statements::Return(s) => match &s.return_value {
+                Some(value) => write!(f, "{} {};", s.token.literal, value),
+                None => write!(f, "{};", s.token.literal),
+            },
+            Statements::Expression(s) => write!(f, "{}", s.expression),
+            Statements::While(s) => {
+                write!(f, "{} ", s.token.literal)?;
+                write_condition(f, &s.condition)?;
+                write!(f, " {}", s.body)
+            }
+            Statements::For(s) => {
+                let variables: Vec<&str> = s.variables.iter().map(|v| v.value.as_str()).collect();
+                write!(
+                    f,
+                    "{} ({} in {}) {}",
+                    s.token.literal,
+                    variables.join(", "),
+                    s.iterable,
+                    s.body
+                )
+            }
+            Statements::Break(s) => write!(f, "{};", s.token.literal),
+            Statements::Continue(s) => write!(f, "{};", s.token.literal),
         }
+    }
+}
+
+impl fmt::Display for BlockStatement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.statements.is_empty() {
+            return write!(f, "{{}}");
+        }
+
+        write!(f, "{{ ")?;
+        write_statements(f, &self.statements)?;
+        write!(f, " }}")
+    }
+}
+
+impl fmt::Display for Expressions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expressions::Identifier(v) => write!(f, "{}", v),
+            Expressions::IntegerLiteral(v) => write!(f, "{}", v.value),
+            Expressions::FloatLiteral(v) => write!(f, "{:?}", v.value),
+            Expressions::StringLiteral(v) => write!(f, "{}", v),
+            Expressions::Boolean(v) => write!(f, "{}", v.value),
+            Expressions::Prefix(v) => write!(f, "({}{})", v.operator, v.right),
+            Expressions::Infix(v) => write!(f, "({} {} {})", v.left, v.operator, v.right),
+            Expressions::Logical(v) => write!(f, "({} {} {})", v.left, v.operator, v.right),
+            Expressions::Assign(a) => write!(f, "({} {} {})", a.target, a.operator, a.value),
+            Expressions::If(i) => write!(f, "{}", i),
+            Expressions::Function(v) => write!(f, "{}", v),
+            Expressions::Call(c) => write!(f, "{}", c),
+            Expressions::Array(a) => write!(f, "{}", a),
+            Expressions::Hash(h) => write!(f, "{}", h),
+            Expressions::Index(i) => write!(f, "({}[{}])", i.left, i.index),
+        }
+    }
+}
+
+impl fmt::Display for Identifier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+impl fmt::Display for StringLiteral {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "\"")?;
+
+        for ch in self.value.chars() {
+            match ch {
+                '"' => write!(f, "\\\"")?,
+                '\\' => write!(f, "\\\\")?,
+                '\n' => write!(f, "\\n")?,
+                '\t' => write!(f, "\\t")?,
+                c if c.is_control() => write!(f, "\\u{{{:x}}}", c as u32)?,
+                c => write!(f, "{}", c)?,
+            }
+        }
+
+        write!(f, "\"")
+    }
+}
+
+impl fmt::Display for IfStatement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "if ")?;
+        write_condition(f, &self.condition)?;
+        write!(f, " {}", self.consequence)?;
+
+        if let Some(alternative) = &self.alternative {
+            write!(f, " else {}", alternative)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for FunctionLiteral {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let parameters: Vec<&str> = self.parameters.iter().map(|p| p.value.as_str()).collect();
+
+        write!(
+            f,
+            "{}({}) {}",
+            self.token.literal,
+            parameters.join(", "),
+            self.body
+        )
+    }
+}
+
+impl fmt::Display for CallExpression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let arguments: Vec<String> = self.arguments.iter().map(|a| a.to_string()).collect();
+
+        write!(f, "{}({})", self.function, arguments.join(", "))
+    }
+}
+
+impl fmt::Display for ArrayLiteral {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let elements: Vec<String> = self.elements.iter().map(|e| e.to_string()).collect();
+
+        write!(f, "[{}]", elements.join(", "))
+    }
+}
+
+impl fmt::Display for HashLiteral {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let pairs: Vec<String> = self
+            .pairs
+            .iter()
+            .map(|(key, value)| format!("{}: {}", key, value))
+            .collect();
 
-        output
+        write!(f, "{{{}}}", pairs.join(", "))
     }
 }
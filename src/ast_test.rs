@@ -1,19 +1,20 @@
 #[cfg(test)]
 mod tests {
     use crate::ast::*;
-    use crate::token;
+    use crate::token::TokenKind;
+    use crate::{lexer, parser, token};
 
     #[test]
     fn test_program_to_string() {
         let program = Program {
             statements: vec![Statements::Let(LetStatement {
-                token: token::new(token::LET, "let".to_string()),
+                token: token::new(TokenKind::Let, "let".to_string()),
                 name: Identifier {
-                    token: token::new(token::IDENT, "myVar".to_string()),
+                    token: token::new(TokenKind::Ident, "myVar".to_string()),
                     value: "myVar".to_string(),
                 },
                 value: Expressions::Identifier(Identifier {
-                    token: token::new(token::IDENT, "anotherVar".to_string()),
+                    token: token::new(TokenKind::Ident, "anotherVar".to_string()),
                     value: "anotherVar".to_string(),
                 }),
             })],
@@ -21,4 +22,35 @@ mod tests {
 
         assert_eq!(program.to_string(), "let myVar = anotherVar;".to_string());
     }
+
+    #[test]
+    fn test_display_keeps_tokens_apart() {
+        let tests = [
+            ("if (x < y) { x } else { y }", "if (x < y) { x } else { y }"),
+            ("if (x) {} else {}", "if (x) {} else {}"),
+            ("fn(a, b) { a; b }", "fn(a, b) { a; b }"),
+            (
+                "while (x) { x += 1; break; }",
+                "while (x) { (x += 1); break; }",
+            ),
+            ("if (a) { 1 }; -1; [2]", "if (a) { 1 }; (-1); [2]"),
+            ("let s = \"a\\\"b\"; s", "let s = \"a\\\"b\"; s"),
+        ];
+
+        for &test in tests.iter() {
+            let mut lexer = lexer::new(test.0.to_string());
+            let mut parser = parser::new(&mut lexer);
+            let program = parser.parse_program();
+
+            assert!(parser.errors().is_empty(), "{}", test.0);
+            assert_eq!(format!("{}", program), test.1);
+
+            let mut lexer = lexer::new(test.1.to_string());
+            let mut parser = parser::new(&mut lexer);
+            let reparsed = parser.parse_program();
+
+            assert!(parser.errors().is_empty(), "{}", test.1);
+            assert_eq!(reparsed.to_string(), test.1);
+        }
+    }
 }
@@ -0,0 +1,58 @@
+use crate::object::Object;
+use std::collections::HashMap;
+
+/// A stack of binding scopes. Lookups walk from the innermost scope outwards
+/// and new bindings always land in the innermost one.
+pub struct Environment {
+    scopes: Vec<HashMap<String, Object>>,
+}
+
+pub fn new() -> Environment {
+    Environment {
+        scopes: vec![HashMap::new()],
+    }
+}
+
+impl Environment {
+    pub fn get(&self, name: &str) -> Option<Object> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .cloned()
+    }
+
+    pub fn set(&mut self, name: String, value: Object) -> Object {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name, value.clone());
+        }
+        value
+    }
+
+    /// Rebinds an existing name in the innermost scope that holds it. Returns
+    /// `false` when the name is not bound anywhere.
+    pub fn assign(&mut self, name: &str, value: Object) -> bool {
+        match self
+            .scopes
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.get_mut(name))
+        {
+            Some(slot) => {
+                *slot = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn push_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    pub fn pop_scope(&mut self) {
+        if self.scopes.len() > 1 {
+            self.scopes.pop();
+        }
+    }
+}
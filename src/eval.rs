@@ -0,0 +1,522 @@
+use crate::environment::Environment;
+use crate::{ast, object::Object};
+use std::collections::BTreeMap;
+
+pub fn eval_program(program: &ast::Program, env: &mut Environment) -> Object {
+    let mut result = Object::Null;
+
+    for statement in &program.statements {
+        result = eval_statement(statement, env);
+
+        match result {
+            Object::ReturnValue(value) => return *value,
+            Object::Error(_) => return result,
+            _ => {}
+        }
+    }
+
+    result
+}
+
+fn eval_block_statement(block: &ast::BlockStatement, env: &mut Environment) -> Object {
+    let mut result = Object::Null;
+
+    for statement in &block.statements {
+        result = eval_statement(statement, env);
+
+        if let Object::ReturnValue(_) | Object::Break | Object::Continue | Object::Error(_) = result
+        {
+            return result;
+        }
+    }
+
+    result
+}
+
+fn eval_statement(statement: &ast::Statements, env: &mut Environment) -> Object {
+    match statement {
+        ast::Statements::Let(s) => {
+            let value = eval_expression(&s.value, env);
+            if value.stops_evaluation() {
+                return value;
+            }
+            env.set(s.name.value.clone(), value);
+            Object::Null
+        }
+        ast::Statements::Return(s) => {
+            let value = match &s.return_value {
+                Some(value) => eval_expression(value, env),
+                None => Object::Null,
+            };
+            if value.stops_evaluation() {
+                return value;
+            }
+            Object::ReturnValue(Box::new(value))
+        }
+        ast::Statements::Expression(s) => eval_expression(&s.expression, env),
+        ast::Statements::While(s) => eval_while_statement(s, env),
+        ast::Statements::For(s) => eval_for_statement(s, env),
+        ast::Statements::Break(_) => Object::Break,
+        ast::Statements::Continue(_) => Object::Continue,
+    }
+}
+
+fn eval_while_statement(statement: &ast::WhileStatement, env: &mut Environment) -> Object {
+    loop {
+        let condition = eval_expression(&statement.condition, env);
+        if condition.stops_evaluation() {
+            return condition;
+        }
+        if !condition.is_truthy() {
+            break;
+        }
+
+        match eval_block_statement(&statement.body, env) {
+            Object::Break => break,
+            result @ Object::ReturnValue(_) | result @ Object::Error(_) => return result,
+            _ => {}
+        }
+    }
+
+    Object::Null
+}
+
+fn eval_for_statement(statement: &ast::ForStatement, env: &mut Environment) -> Object {
+    let iterable = eval_expression(&statement.iterable, env);
+    if iterable.stops_evaluation() {
+        return iterable;
+    }
+
+    // Each item is a `(key, value)` pair: `(index, element)` for arrays. A
+    // single loop variable takes the element of an array or the key of a hash.
+    let (items, single_takes_key): (Vec<(Object, Object)>, bool) = match iterable {
+        Object::Array(elements) => (
+            elements
+                .into_iter()
+                .enumerate()
+                .map(|(i, element)| (Object::Integer(i as i64), element))
+                .collect(),
+            false,
+        ),
+        Object::Hash(pairs) => (
+            pairs
+                .into_iter()
+                .map(|(key, value)| (key.to_object(), value))
+                .collect(),
+            true,
+        ),
+        other => return Object::Error(format!("not iterable: {}", other.type_name())),
+    };
+
+    for (key, value) in items {
+        env.push_scope();
+        match statement.variables.as_slice() {
+            [item] => {
+                let item_value = if single_takes_key { key } else { value };
+                env.set(item.value.clone(), item_value);
+            }
+            [key_name, value_name] => {
+                env.set(key_name.value.clone(), key);
+                env.set(value_name.value.clone(), value);
+            }
+            _ => {}
+        }
+        let result = eval_block_statement(&statement.body, env);
+        env.pop_scope();
+
+        match result {
+            Object::Break => break,
+            result @ Object::ReturnValue(_) | result @ Object::Error(_) => return result,
+            _ => {}
+        }
+    }
+
+    Object::Null
+}
+
+fn eval_expression(expression: &ast::Expressions, env: &mut Environment) -> Object {
+    match expression {
+        ast::Expressions::Identifier(i) => eval_identifier(i, env),
+        ast::Expressions::IntegerLiteral(i) => Object::Integer(i.value),
+        ast::Expressions::FloatLiteral(f) => Object::Float(f.value),
+        ast::Expressions::StringLiteral(s) => Object::String(s.value.clone()),
+        ast::Expressions::Boolean(b) => Object::Boolean(b.value),
+        ast::Expressions::Prefix(p) => {
+            let right = eval_expression(&p.right, env);
+            if right.stops_evaluation() {
+                return right;
+            }
+            eval_prefix_expression(&p.operator, right)
+        }
+        ast::Expressions::Infix(i) => {
+            let left = eval_expression(&i.left, env);
+            if left.stops_evaluation() {
+                return left;
+            }
+            let right = eval_expression(&i.right, env);
+            if right.stops_evaluation() {
+                return right;
+            }
+            eval_infix_expression(&i.operator, left, right)
+        }
+        ast::Expressions::Logical(l) => eval_logical_expression(l, env),
+        ast::Expressions::Assign(a) => eval_assign_expression(a, env),
+        ast::Expressions::If(i) => eval_if_expression(i, env),
+        ast::Expressions::Array(a) => match eval_expressions(&a.elements, env) {
+            Ok(elements) => Object::Array(elements),
+            Err(error) => error,
+        },
+        ast::Expressions::Hash(h) => eval_hash_literal(h, env),
+        ast::Expressions::Index(i) => {
+            let left = eval_expression(&i.left, env);
+            if left.stops_evaluation() {
+                return left;
+            }
+            let index = eval_expression(&i.index, env);
+            if index.stops_evaluation() {
+                return index;
+            }
+            eval_index_expression(left, index)
+        }
+        ast::Expressions::Function(_) | ast::Expressions::Call(_) => {
+            Object::Error(format!("unsupported expression: {}", expression))
+        }
+    }
+}
+
+fn eval_expressions(
+    expressions: &[ast::Expressions],
+    env: &mut Environment,
+) -> Result<Vec<Object>, Object> {
+    let mut result = Vec::with_capacity(expressions.len());
+
+    for expression in expressions {
+        let evaluated = eval_expression(expression, env);
+        if evaluated.stops_evaluation() {
+            return Err(evaluated);
+        }
+        result.push(evaluated);
+    }
+
+    Ok(result)
+}
+
+fn eval_identifier(identifier: &ast::Identifier, env: &Environment) -> Object {
+    match env.get(&identifier.value) {
+        Some(value) => value,
+        None => Object::Error(format!("identifier not found: {}", identifier.value)),
+    }
+}
+
+fn eval_prefix_expression(operator: &str, right: Object) -> Object {
+    match (operator, right) {
+        ("!", right) => Object::Boolean(!right.is_truthy()),
+        ("-", Object::Integer(v)) => match v.checked_neg() {
+            Some(v) => Object::Integer(v),
+            None => Object::Error("integer overflow".to_string()),
+        },
+        ("-", Object::Float(v)) => Object::Float(-v),
+        ("~", Object::Integer(v)) => Object::Integer(!v),
+        (operator, right) => Object::Error(format!(
+            "unknown operator: {}{}",
+            operator,
+            right.type_name()
+        )),
+    }
+}
+
+fn eval_infix_expression(operator: &str, left: Object, right: Object) -> Object {
+    match (left, right) {
+        (Object::Integer(l), Object::Integer(r)) => eval_integer_infix_expression(operator, l, r),
+        (Object::Float(l), Object::Float(r)) => eval_float_infix_expression(operator, l, r),
+        (Object::Integer(l), Object::Float(r)) => {
+            eval_float_infix_expression(operator, l as f64, r)
+        }
+        (Object::Float(l), Object::Integer(r)) => {
+            eval_float_infix_expression(operator, l, r as f64)
+        }
+        (Object::String(l), Object::String(r)) => match operator {
+            "+" => Object::String(l + &r),
+            "==" => Object::Boolean(l == r),
+            "!=" => Object::Boolean(l != r),
+            _ => Object::Error(format!("unknown operator: STRING {} STRING", operator)),
+        },
+        (Object::Boolean(l), Object::Boolean(r)) => match operator {
+            "==" => Object::Boolean(l == r),
+            "!=" => Object::Boolean(l != r),
+            _ => Object::Error(format!("unknown operator: BOOLEAN {} BOOLEAN", operator)),
+        },
+        (left, right) => {
+            if left.type_name() != right.type_name() {
+                return Object::Error(format!(
+                    "type mismatch: {} {} {}",
+                    left.type_name(),
+                    operator,
+                    right.type_name()
+                ));
+            }
+            Object::Error(format!(
+                "unknown operator: {} {} {}",
+                left.type_name(),
+                operator,
+                right.type_name()
+            ))
+        }
+    }
+}
+
+fn eval_integer_infix_expression(operator: &str, left: i64, right: i64) -> Object {
+    let value = match operator {
+        "+" => left.checked_add(right),
+        "-" => left.checked_sub(right),
+        "*" => left.checked_mul(right),
+        "/" | "%" => {
+            if right == 0 {
+                return Object::Error("division by zero".to_string());
+            }
+            if operator == "/" {
+                left.checked_div(right)
+            } else {
+                left.checked_rem(right)
+            }
+        }
+        "**" => {
+            if right < 0 {
+                return Object::Error("negative exponent".to_string());
+            }
+            // These bases stay in range for any exponent, however large.
+            match left {
+                0 | 1 => Some(if right == 0 { 1 } else { left }),
+                -1 => Some(if right % 2 == 0 { 1 } else { -1 }),
+                _ if right > u32::MAX as i64 => None,
+                _ => left.checked_pow(right as u32),
+            }
+        }
+        "&" => Some(left & right),
+        "|" => Some(left | right),
+        "^" => Some(left ^ right),
+        "<<" | ">>" => {
+            if !(0..64).contains(&right) {
+                return Object::Error(format!("shift amount out of range: {}", right));
+            }
+            if operator == "<<" {
+                left.checked_shl(right as u32)
+            } else {
+                left.checked_shr(right as u32)
+            }
+        }
+        "<" => return Object::Boolean(left < right),
+        ">" => return Object::Boolean(left > right),
+        "<=" => return Object::Boolean(left <= right),
+        ">=" => return Object::Boolean(left >= right),
+        "==" => return Object::Boolean(left == right),
+        "!=" => return Object::Boolean(left != right),
+        _ => return Object::Error(format!("unknown operator: INTEGER {} INTEGER", operator)),
+    };
+
+    match value {
+        Some(v) => Object::Integer(v),
+        None => Object::Error("integer overflow".to_string()),
+    }
+}
+
+fn eval_hash_literal(hash: &ast::HashLiteral, env: &mut Environment) -> Object {
+    let mut pairs = BTreeMap::new();
+
+    for (key_expression, value_expression) in &hash.pairs {
+        let key = eval_expression(key_expression, env);
+        if key.stops_evaluation() {
+            return key;
+        }
+
+        let hash_key = match key.hash_key() {
+            Some(hash_key) => hash_key,
+            None => return Object::Error(format!("unusable as hash key: {}", key.type_name())),
+        };
+
+        let value = eval_expression(value_expression, env);
+        if value.stops_evaluation() {
+            return value;
+        }
+
+        pairs.insert(hash_key, value);
+    }
+
+    Object::Hash(pairs)
+}
+
+fn eval_float_infix_expression(operator: &str, left: f64, right: f64) -> Object {
+    match operator {
+        "+" => Object::Float(left + right),
+        "-" => Object::Float(left - right),
+        "*" => Object::Float(left * right),
+        "/" => Object::Float(left / right),
+        "%" => Object::Float(left % right),
+        "**" => Object::Float(left.powf(right)),
+        "<" => Object::Boolean(left < right),
+        ">" => Object::Boolean(left > right),
+        "<=" => Object::Boolean(left <= right),
+        ">=" => Object::Boolean(left >= right),
+        "==" => Object::Boolean(left == right),
+        "!=" => Object::Boolean(left != right),
+        _ => Object::Error(format!("unknown operator: FLOAT {} FLOAT", operator)),
+    }
+}
+
+fn eval_index_expression(left: Object, index: Object) -> Object {
+    match (left, index) {
+        (Object::Hash(pairs), index) => match index.hash_key() {
+            Some(key) => pairs.get(&key).cloned().unwrap_or(Object::Null),
+            None => Object::Error(format!("unusable as hash key: {}", index.type_name())),
+        },
+        (Object::Array(elements), Object::Integer(i)) => {
+            if i < 0 || i as usize >= elements.len() {
+                return Object::Null;
+            }
+            elements[i as usize].clone()
+        }
+        (left, _) => Object::Error(format!(
+            "index operator not supported: {}",
+            left.type_name()
+        )),
+    }
+}
+
+fn eval_logical_expression(expression: &ast::LogicalExpression, env: &mut Environment) -> Object {
+    let left = eval_expression(&expression.left, env);
+    if left.stops_evaluation() {
+        return left;
+    }
+
+    match (expression.operator.as_str(), left.is_truthy()) {
+        ("&&", false) => return Object::Boolean(false),
+        ("||", true) => return Object::Boolean(true),
+        _ => {}
+    }
+
+    let right = eval_expression(&expression.right, env);
+    if right.stops_evaluation() {
+        return right;
+    }
+
+    Object::Boolean(right.is_truthy())
+}
+
+fn eval_assign_expression(expression: &ast::AssignExpression, env: &mut Environment) -> Object {
+    // The target's indexes are evaluated once, so a compound operator reads
+    // and writes the same element even when an index has side effects.
+    let (name, indexes) = match assignment_place(&expression.target, env) {
+        Ok(place) => place,
+        Err(error) => return error,
+    };
+
+    let mut value = eval_expression(&expression.value, env);
+    if value.stops_evaluation() {
+        return value;
+    }
+
+    let root = match env.get(&name) {
+        Some(root) => root,
+        None => return Object::Error(format!("identifier not found: {}", name)),
+    };
+
+    // Compound operators such as `+=` apply the bare operator to the current
+    // value of the target first.
+    let operator = expression.operator.trim_end_matches('=');
+    if !operator.is_empty() {
+        let mut current = root.clone();
+        for index in indexes.iter() {
+            current = eval_index_expression(current, index.clone());
+            if current.is_error() {
+                return current;
+            }
+        }
+        value = eval_infix_expression(operator, current, value);
+        if value.is_error() {
+            return value;
+        }
+    }
+
+    match store(root, &indexes, value.clone()) {
+        Ok(updated) => {
+            env.assign(&name, updated);
+            value
+        }
+        Err(error) => error,
+    }
+}
+
+// Resolves an assignment target to the variable it names and the evaluated
+// indexes, outermost first, that lead from it to the assigned element.
+fn assignment_place(
+    target: &ast::Expressions,
+    env: &mut Environment,
+) -> Result<(String, Vec<Object>), Object> {
+    match target {
+        ast::Expressions::Identifier(i) => Ok((i.value.clone(), vec![])),
+        ast::Expressions::Index(i) => {
+            let (name, mut indexes) = assignment_place(&i.left, env)?;
+            let index = eval_expression(&i.index, env);
+            if index.stops_evaluation() {
+                return Err(index);
+            }
+            indexes.push(index);
+            Ok((name, indexes))
+        }
+        _ => Err(Object::Error(format!(
+            "invalid assignment target: {}",
+            target
+        ))),
+    }
+}
+
+// Returns `container` with the element reached through `indexes` replaced by
+// `value`.
+fn store(container: Object, indexes: &[Object], value: Object) -> Result<Object, Object> {
+    let (index, rest) = match indexes.split_first() {
+        Some(split) => split,
+        None => return Ok(value),
+    };
+
+    match (container, index) {
+        (Object::Array(mut elements), Object::Integer(idx)) => {
+            let idx = *idx;
+            if idx < 0 || idx as usize >= elements.len() {
+                return Err(Object::Error(format!("index out of range: {}", idx)));
+            }
+            let element = std::mem::replace(&mut elements[idx as usize], Object::Null);
+            elements[idx as usize] = store(element, rest, value)?;
+            Ok(Object::Array(elements))
+        }
+        (Object::Hash(mut pairs), index) => match index.hash_key() {
+            Some(key) => {
+                let element = pairs.remove(&key).unwrap_or(Object::Null);
+                pairs.insert(key, store(element, rest, value)?);
+                Ok(Object::Hash(pairs))
+            }
+            None => Err(Object::Error(format!(
+                "unusable as hash key: {}",
+                index.type_name()
+            ))),
+        },
+        (container, _) => Err(Object::Error(format!(
+            "index operator not supported: {}",
+            container.type_name()
+        ))),
+    }
+}
+
+fn eval_if_expression(expression: &ast::IfStatement, env: &mut Environment) -> Object {
+    let condition = eval_expression(&expression.condition, env);
+    if condition.stops_evaluation() {
+        return condition;
+    }
+
+    if condition.is_truthy() {
+        eval_block_statement(&expression.consequence, env)
+    } else if let Some(alternative) = &expression.alternative {
+        eval_block_statement(alternative, env)
+    } else {
+        Object::Null
+    }
+}
@@ -0,0 +1,544 @@
+#[cfg(test)]
+mod tests {
+    use crate::eval::*;
+    use crate::object::Object;
+    use crate::{environment, lexer, parser};
+
+    fn eval_input(input: &str) -> Object {
+        let mut lexer = lexer::new(input.to_string());
+        let mut parser = parser::new(&mut lexer);
+        let program = parser.parse_program();
+        let mut env = environment::new();
+
+        eval_program(&program, &mut env)
+    }
+
+    #[test]
+    fn test_eval_integer_expression() {
+        let tests = [
+            ("5", 5),
+            ("10", 10),
+            ("-5", -5),
+            ("-10", -10),
+            ("5 + 5 + 5 + 5 - 10", 10),
+            ("2 * 2 * 2 * 2 * 2", 32),
+            ("-50 + 100 + -50", 0),
+            ("5 * 2 + 10", 20),
+            ("5 + 2 * 10", 25),
+            ("20 + 2 * -10", 0),
+            ("50 / 2 * 2 + 10", 60),
+            ("2 * (5 + 10)", 30),
+            ("3 * 3 * 3 + 10", 37),
+            ("3 * (3 * 3) + 10", 37),
+            ("(5 + 10 * 2 + 15 / 3) * 2 + -10", 50),
+            ("7 % 3", 1),
+            ("-7 % 3", -1),
+            ("2 ** 10", 1024),
+            ("2 ** 3 ** 2", 512),
+            ("-2 ** 2", -4),
+            ("(-2) ** 2", 4),
+            ("2 ** 0", 1),
+            ("1 ** 5000000000", 1),
+            ("0 ** 5000000000", 0),
+            ("0 ** 0", 1),
+            ("(-1) ** 5000000000", 1),
+            ("(-1) ** 5000000001", -1),
+            ("12 & 10", 8),
+            ("12 | 10", 14),
+            ("12 ^ 10", 6),
+            ("~0", -1),
+            ("~5 & 0xF", 10),
+            ("0b1010 | 0o5", 15),
+            ("1_000 * 1_000", 1_000_000),
+            ("1 << 4", 16),
+            ("-16 >> 2", -4),
+            ("1 + 2 << 1", 6),
+        ];
+
+        for &test in tests.iter() {
+            assert_eq!(eval_input(test.0), Object::Integer(test.1), "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_eval_float_expression() {
+        let tests = [
+            ("3.5", 3.5),
+            ("-2.5", -2.5),
+            ("1.5 + 1", 2.5),
+            ("1 + 1.5", 2.5),
+            ("5 / 2.0", 2.5),
+            ("2.0 * 3 + 0.5", 6.5),
+            ("7.5 % 2", 1.5),
+            ("2 ** 0.5 ** 2", 2f64.powf(0.25)),
+            ("1e3 - 1", 999.0),
+        ];
+
+        for &test in tests.iter() {
+            assert_eq!(eval_input(test.0), Object::Float(test.1), "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_eval_boolean_expression() {
+        let tests = [
+            ("true", true),
+            ("false", false),
+            ("1 < 2", true),
+            ("1 > 2", false),
+            ("1 < 1", false),
+            ("1 == 1", true),
+            ("1 != 1", false),
+            ("1 == 2", false),
+            ("1 != 2", true),
+            ("true == true", true),
+            ("false == false", true),
+            ("true == false", false),
+            ("true != false", true),
+            ("(1 < 2) == true", true),
+            ("(1 > 2) == true", false),
+            ("1 <= 1", true),
+            ("2 <= 1", false),
+            ("1 >= 1", true),
+            ("1 >= 2", false),
+            ("1.5 < 2", true),
+            ("2 <= 1.5", false),
+            ("1.0 == 1", true),
+            ("0.1 + 0.2 != 0.3", true),
+            ("true && true", true),
+            ("true && false", false),
+            ("false || true", true),
+            ("false || false", false),
+            ("1 < 2 && 2 < 3", true),
+            ("1 && 0", true),
+            ("if (false) { 1 } && true", false),
+        ];
+
+        for &test in tests.iter() {
+            assert_eq!(eval_input(test.0), Object::Boolean(test.1), "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_bang_operator() {
+        let tests = [
+            ("!true", false),
+            ("!false", true),
+            ("!5", false),
+            ("!!true", true),
+            ("!!false", false),
+            ("!!5", true),
+        ];
+
+        for &test in tests.iter() {
+            assert_eq!(eval_input(test.0), Object::Boolean(test.1), "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_if_else_expression() {
+        let tests = [
+            ("if (true) { 10 }", Object::Integer(10)),
+            ("if (false) { 10 }", Object::Null),
+            ("if (1) { 10 }", Object::Integer(10)),
+            ("if (1 < 2) { 10 }", Object::Integer(10)),
+            ("if (1 > 2) { 10 }", Object::Null),
+            ("if (1 > 2) { 10 } else { 20 }", Object::Integer(20)),
+            ("if (1 < 2) { 10 } else { 20 }", Object::Integer(10)),
+        ];
+
+        for test in tests.iter() {
+            assert_eq!(eval_input(test.0), test.1, "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_return_statements() {
+        let tests = [
+            ("return 10;", Object::Integer(10)),
+            ("return 10; 9;", Object::Integer(10)),
+            ("return 2 * 5; 9;", Object::Integer(10)),
+            ("9; return 2 * 5; 9;", Object::Integer(10)),
+            ("return; 9;", Object::Null),
+            (
+                "if (10 > 1) { if (10 > 1) { return 10; } return 1; }",
+                Object::Integer(10),
+            ),
+            ("if (true) { return; } 1", Object::Null),
+        ];
+
+        for test in tests.iter() {
+            assert_eq!(eval_input(test.0), test.1, "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_while_statements() {
+        let tests = [
+            ("while (false) { 1 }", Object::Null),
+            ("while (true) { break; } 5", Object::Integer(5)),
+            ("while (true) { if (true) { break; } 1 }", Object::Null),
+            ("while (true) { return 7; }", Object::Integer(7)),
+            (
+                "while (true) { while (true) { break; } return 3; }",
+                Object::Integer(3),
+            ),
+            (
+                "while (missing) { 1 }",
+                Object::Error("identifier not found: missing".to_string()),
+            ),
+        ];
+
+        for test in tests.iter() {
+            assert_eq!(eval_input(test.0), test.1, "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_loop_control_inside_expressions() {
+        let tests = [
+            (
+                "let i = 0; while (i < 3) { let y = if (true) { break; }; i += 1; } i",
+                Object::Integer(0),
+            ),
+            (
+                "let i = 0; let n = 0; while (i < 3) { i += 1; let t = [1, if (true) { continue; }]; n += 1; } i * 10 + n",
+                Object::Integer(30),
+            ),
+            (
+                "let c = true; while (true) { 1 + if (c) { break; }; } 5",
+                Object::Integer(5),
+            ),
+            ("while (true) { -if (true) { break; }; } 6", Object::Integer(6)),
+            (
+                "while (true) { let h = {1: if (true) { break; }}; } 7",
+                Object::Integer(7),
+            ),
+            (
+                "while (true) { [1][if (true) { break; }]; } 8",
+                Object::Integer(8),
+            ),
+            (
+                "let x = 0; while (x < 2) { x += if (true) { break; }; } x",
+                Object::Integer(0),
+            ),
+            (
+                "while (true) { let x = if (true) { return 9; }; } 1",
+                Object::Integer(9),
+            ),
+        ];
+
+        for test in tests.iter() {
+            assert_eq!(eval_input(test.0), test.1, "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_for_statements() {
+        let tests = [
+            ("for (x in []) { 1 }", Object::Null),
+            (
+                "for (x in [1, 2, 3]) { if (x == 2) { return x * 10; } }",
+                Object::Integer(20),
+            ),
+            (
+                "for (x in [1, 2, 3]) { if (x < 3) { continue; } return x; }",
+                Object::Integer(3),
+            ),
+            (
+                "for (x in [1, 2]) { break; return x; } 4",
+                Object::Integer(4),
+            ),
+            (
+                "for (i, x in [5, 6]) { if (x == 6) { return i; } }",
+                Object::Integer(1),
+            ),
+            (
+                r#"for (k in {"b": 2, "a": 1}) { return k; }"#,
+                Object::String("a".to_string()),
+            ),
+            (
+                r#"for (k, v in {"a": 1, "b": 2}) { if (v == 2) { return k; } }"#,
+                Object::String("b".to_string()),
+            ),
+            ("let x = 10; for (x in [1]) { } x", Object::Integer(10)),
+            (
+                "let i = 0; for (x in [1, 2, 3]) { let r = if (x == 2) { break; } else { 0 }; i += x } i",
+                Object::Integer(1),
+            ),
+            (
+                "let i = 0; for (x in [1, 2, 3]) { i += [x, if (x == 2) { continue; }][0] } i",
+                Object::Integer(4),
+            ),
+            (
+                "for (x in [1]) { let y = x; } y",
+                Object::Error("identifier not found: y".to_string()),
+            ),
+            (
+                "for (x in 5) { }",
+                Object::Error("not iterable: INTEGER".to_string()),
+            ),
+        ];
+
+        for test in tests.iter() {
+            assert_eq!(eval_input(test.0), test.1, "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_let_statements() {
+        let tests = [
+            ("let a = 5; a;", 5),
+            ("let a = 5 * 5; a;", 25),
+            ("let a = 5; let b = a; b;", 5),
+            ("let a = 5; let b = a; let c = a + b + 5; c;", 15),
+        ];
+
+        for &test in tests.iter() {
+            assert_eq!(eval_input(test.0), Object::Integer(test.1), "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_assign_expressions() {
+        let tests = [
+            ("let x = 1; x = x + 1; x", Object::Integer(2)),
+            ("let x = 1; x = 5", Object::Integer(5)),
+            (
+                "let x = 10; x += 5; x -= 3; x *= 2; x /= 4; x",
+                Object::Integer(6),
+            ),
+            ("let x = 1.5; x *= 2; x", Object::Float(3.0)),
+            (
+                r#"let s = "a"; s += "b"; s"#,
+                Object::String("ab".to_string()),
+            ),
+            ("let a = 1; let b = 2; a = b = 3; a + b", Object::Integer(6)),
+            (
+                "let i = 0; let sum = 0; while (i < 5) { i += 1; sum += i; } sum",
+                Object::Integer(15),
+            ),
+            (
+                "let sum = 0; for (x in [1, 2, 3]) { sum += x; } sum",
+                Object::Integer(6),
+            ),
+            (
+                "let arr = [1, 2, 3]; arr[0] = 5; arr[0]",
+                Object::Integer(5),
+            ),
+            (
+                "let arr = [1, 2, 3]; arr[2] += 10; arr[2]",
+                Object::Integer(13),
+            ),
+            (
+                r#"let m = {"k": 1}; m["k"] = 2; m["j"] = 3; m["k"] + m["j"]"#,
+                Object::Integer(5),
+            ),
+            (
+                "let grid = [[1, 2], [3, 4]]; grid[1][0] = 7; grid[1][0]",
+                Object::Integer(7),
+            ),
+            (
+                r#"let m = {"xs": [1]}; m["xs"][0] *= 9; m["xs"][0]"#,
+                Object::Integer(9),
+            ),
+            (
+                "let i = 0; let a = [10, 20, 30]; a[i += 1] += 5; i",
+                Object::Integer(1),
+            ),
+            (
+                "let i = 0; let a = [10, 20, 30]; a[i += 1] += 5; a[1] * 100 + a[2]",
+                Object::Integer(2530),
+            ),
+            (
+                "let i = 0; let g = [[0], [0]]; g[i += 1][0] -= 7; i * 10 + g[1][0]",
+                Object::Integer(3),
+            ),
+        ];
+
+        for test in tests.iter() {
+            assert_eq!(eval_input(test.0), test.1, "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_assign_errors() {
+        let tests = [
+            ("x = 1", "identifier not found: x"),
+            ("x += 1", "identifier not found: x"),
+            ("let x = true; x += 1", "type mismatch: BOOLEAN + INTEGER"),
+            ("let x = 1; x /= 0", "division by zero"),
+            ("let arr = [1]; arr[1] = 2", "index out of range: 1"),
+            ("let arr = [1]; arr[-1] = 2", "index out of range: -1"),
+            ("let m = {}; m[[1]] = 2", "unusable as hash key: ARRAY"),
+            (
+                "let n = 1; n[0] = 2",
+                "index operator not supported: INTEGER",
+            ),
+        ];
+
+        for &test in tests.iter() {
+            assert_eq!(
+                eval_input(test.0),
+                Object::Error(test.1.to_string()),
+                "{}",
+                test.0
+            );
+        }
+    }
+
+    #[test]
+    fn test_string_expressions() {
+        let tests = [
+            (
+                r#""Hello World!""#,
+                Object::String("Hello World!".to_string()),
+            ),
+            (
+                r#""Hello" + " " + "World!""#,
+                Object::String("Hello World!".to_string()),
+            ),
+            (r#""a" == "a""#, Object::Boolean(true)),
+            (r#""a" != "a""#, Object::Boolean(false)),
+            (
+                r#""a" - "b""#,
+                Object::Error("unknown operator: STRING - STRING".to_string()),
+            ),
+        ];
+
+        for test in tests.iter() {
+            assert_eq!(eval_input(test.0), test.1, "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_array_literals() {
+        assert_eq!(
+            eval_input("[1, 2 * 2, 3 + 3]"),
+            Object::Array(vec![
+                Object::Integer(1),
+                Object::Integer(4),
+                Object::Integer(6)
+            ])
+        );
+    }
+
+    #[test]
+    fn test_array_index_expressions() {
+        let tests = [
+            ("[1, 2, 3][0]", Object::Integer(1)),
+            ("[1, 2, 3][1]", Object::Integer(2)),
+            ("let i = 0; [1][i];", Object::Integer(1)),
+            ("[1, 2, 3][1 + 1];", Object::Integer(3)),
+            ("let a = [1, 2, 3]; a[0] + a[1] + a[2];", Object::Integer(6)),
+            ("[[1, 2], [3, 4]][1][0]", Object::Integer(3)),
+            ("[1, 2, 3][3]", Object::Null),
+            ("[1, 2, 3][-1]", Object::Null),
+            (
+                "1[0]",
+                Object::Error("index operator not supported: INTEGER".to_string()),
+            ),
+        ];
+
+        for test in tests.iter() {
+            assert_eq!(eval_input(test.0), test.1, "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_hash_literals() {
+        let input = r#"
+          let two = "two";
+          {"one": 10 - 9, two: 1 + 1, "thr" + "ee": 6 / 2, 4: 4, true: 5, false: 6}
+        "#;
+
+        assert_eq!(
+            eval_input(input).inspect(),
+            "{4: 4, false: 6, true: 5, one: 1, three: 3, two: 2}"
+        );
+    }
+
+    #[test]
+    fn test_hash_index_expressions() {
+        let tests = [
+            (r#"{"foo": 5}["foo"]"#, Object::Integer(5)),
+            (r#"{"foo": 5}["bar"]"#, Object::Null),
+            (r#"let key = "foo"; {"foo": 5}[key]"#, Object::Integer(5)),
+            (r#"{}["foo"]"#, Object::Null),
+            ("{5: 5}[5]", Object::Integer(5)),
+            ("{true: 5}[true]", Object::Integer(5)),
+            (
+                r#"{"name": "Monkey"}[fn(x) { x }];"#,
+                Object::Error("unsupported expression: fn(x) { x }".to_string()),
+            ),
+            (
+                "{[1]: 2}",
+                Object::Error("unusable as hash key: ARRAY".to_string()),
+            ),
+            (
+                "{1: 2}[[1]]",
+                Object::Error("unusable as hash key: ARRAY".to_string()),
+            ),
+        ];
+
+        for test in tests.iter() {
+            assert_eq!(eval_input(test.0), test.1, "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_logical_short_circuit() {
+        let tests = [
+            ("false && missing", Object::Boolean(false)),
+            ("true || missing", Object::Boolean(true)),
+            (
+                "true && missing",
+                Object::Error("identifier not found: missing".to_string()),
+            ),
+            (
+                "false || missing",
+                Object::Error("identifier not found: missing".to_string()),
+            ),
+        ];
+
+        for test in tests.iter() {
+            assert_eq!(eval_input(test.0), test.1, "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_error_handling() {
+        let tests = [
+            ("5 + true;", "type mismatch: INTEGER + BOOLEAN"),
+            ("5 + true; 5;", "type mismatch: INTEGER + BOOLEAN"),
+            ("-true", "unknown operator: -BOOLEAN"),
+            ("true + false;", "unknown operator: BOOLEAN + BOOLEAN"),
+            ("5; true + false; 5", "unknown operator: BOOLEAN + BOOLEAN"),
+            (
+                "if (10 > 1) { true + false; }",
+                "unknown operator: BOOLEAN + BOOLEAN",
+            ),
+            ("foobar", "identifier not found: foobar"),
+            ("10 / 0", "division by zero"),
+            ("10 % 0", "division by zero"),
+            ("2 ** -1", "negative exponent"),
+            ("2 ** 64", "integer overflow"),
+            ("2 ** 5000000000", "integer overflow"),
+            ("1 << 64", "shift amount out of range: 64"),
+            ("1 >> -1", "shift amount out of range: -1"),
+            ("~true", "unknown operator: ~BOOLEAN"),
+            ("1.5 & 1", "unknown operator: FLOAT & FLOAT"),
+            ("~1.5", "unknown operator: ~FLOAT"),
+            ("{1.5: 1}", "unusable as hash key: FLOAT"),
+        ];
+
+        for &test in tests.iter() {
+            assert_eq!(
+                eval_input(test.0),
+                Object::Error(test.1.to_string()),
+                "{}",
+                test.0
+            );
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use crate::token::TokenKind;
+use crate::{lexer, parser, pretty};
+use std::fs;
+use std::io;
+
+const USAGE: &str = "usage: monkey-lang fmt [--check] [FILE]...";
+
+/// Formats Monkey `source`, keeping its comments. Source that does not parse
+/// is returned as its parse errors rather than formatted from a partial tree,
+/// which would drop code.
+pub fn format(source: &str) -> Result<String, Vec<parser::ParseError>> {
+    let mut lexer = lexer::new(source.to_string());
+    let mut parser = parser::new(&mut lexer);
+    let program = parser.parse_program();
+
+    if !parser.errors().is_empty() {
+        return Err(parser.errors().to_vec());
+    }
+
+    let mut lexer = lexer::with_comments(source.to_string());
+    let mut tokens = vec![];
+    loop {
+        let token = lexer.next_token();
+        let eof = token.token_type == TokenKind::Eof;
+        tokens.push(token);
+        if eof {
+            break;
+        }
+    }
+
+    Ok(pretty::print_with_comments(&program, &tokens))
+}
+
+/// Runs the `fmt` subcommand with the arguments that follow it. Files are
+/// rewritten in place, or with no files stdin is formatted to stdout. With
+/// `--check` nothing is written: unformatted files are listed and the exit
+/// status is 1.
+pub fn run<R: io::Read, W: io::Write, E: io::Write>(
+    args: &[String],
+    mut stdin: R,
+    stdout: &mut W,
+    stderr: &mut E,
+) -> i32 {
+    let mut check = false;
+    let mut paths = vec![];
+
+    for arg in args {
+        match arg.as_str() {
+            "--check" => check = true,
+            flag if flag.starts_with('-') => {
+                writeln!(stderr, "unknown flag {}\n{}", flag, USAGE).unwrap();
+                return 2;
+            }
+            path => paths.push(path),
+        }
+    }
+
+    if paths.is_empty() {
+        let mut source = String::new();
+        if let Err(error) = stdin.read_to_string(&mut source) {
+            writeln!(stderr, "<stdin>: {}", error).unwrap();
+            return 1;
+        }
+
+        return match format(&source) {
+            Ok(formatted) if check => {
+                if formatted == source {
+                    0
+                } else {
+                    writeln!(stdout, "<stdin>").unwrap();
+                    1
+                }
+            }
+            Ok(formatted) => {
+                stdout.write_all(formatted.as_bytes()).unwrap();
+                0
+            }
+            Err(errors) => {
+                print_errors(stderr, "<stdin>", &errors);
+                1
+            }
+        };
+    }
+
+    let mut status = 0;
+
+    for path in paths {
+        let source = match fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(error) => {
+                writeln!(stderr, "{}: {}", path, error).unwrap();
+                status = 1;
+                continue;
+            }
+        };
+
+        let formatted = match format(&source) {
+            Ok(formatted) => formatted,
+            Err(errors) => {
+                print_errors(stderr, path, &errors);
+                status = 1;
+                continue;
+            }
+        };
+
+        if formatted == source {
+            continue;
+        }
+
+        if check {
+            writeln!(stdout, "{}", path).unwrap();
+            status = 1;
+        } else if let Err(error) = fs::write(path, formatted) {
+            writeln!(stderr, "{}: {}", path, error).unwrap();
+            status = 1;
+        }
+    }
+
+    status
+}
+
+fn print_errors<E: io::Write>(stderr: &mut E, name: &str, errors: &[parser::ParseError]) {
+    for error in errors {
+        writeln!(stderr, "{}: {}", name, error).unwrap();
+    }
+}
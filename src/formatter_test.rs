@@ -0,0 +1,324 @@
+#[cfg(test)]
+mod tests {
+    use crate::formatter::*;
+
+    fn format_ok(source: &str) -> String {
+        let formatted = format(source).unwrap();
+        assert_eq!(format(&formatted).unwrap(), formatted, "not idempotent");
+        formatted
+    }
+
+    #[test]
+    fn test_format_normalises_layout() {
+        let source = "let add=fn(a,b){\nreturn a+b}\n\n\n\nlet x=add(1,2);if(x>2){x}else{0}";
+        let expected = "let add = fn(a, b) {
+    return a + b;
+};
+
+let x = add(1, 2);
+if (x > 2) {
+    x;
+} else {
+    0;
+}
+";
+
+        assert_eq!(format_ok(source), expected);
+    }
+
+    #[test]
+    fn test_format_keeps_comments() {
+        let source = "// header
+// second line
+
+let x = 1; // trailing
+/* block */ let y = 2;
+let m = {
+  // inside
+  \"k\": 1
+};
+while (x) {
+  // first
+  x -= 1; /* after */ // end of line
+  // last
+}
+fn() {
+  // only a comment
+};
+// footer
+";
+        let expected = "// header
+// second line
+
+let x = 1; // trailing
+/* block */
+let y = 2;
+let m = {
+    // inside
+    \"k\": 1
+};
+while (x) {
+    // first
+    x -= 1; /* after */ // end of line
+    // last
+}
+fn() {
+    // only a comment
+};
+// footer
+";
+
+        assert_eq!(format_ok(source), expected);
+    }
+
+    #[test]
+    fn test_format_comment_only_sources() {
+        assert_eq!(format_ok(""), "");
+        assert_eq!(format_ok("  // just this  \n"), "// just this\n");
+        assert_eq!(
+            format_ok("/* a\n   b */\n\n// c"),
+            "/* a\n   b */\n\n// c\n"
+        );
+    }
+
+    #[test]
+    fn test_format_trailing_comment_after_block() {
+        let source = "if (a) { b } // done\nc";
+
+        assert_eq!(format_ok(source), "if (a) {\n    b;\n} // done\nc;\n");
+    }
+
+    #[test]
+    fn test_format_keeps_comments_inside_expressions() {
+        let source = "let h = {\"a\": 1, /* mid */ 2: [1,2]};
+let n = 1 + /* one */ 2 * f(/* x */ x /* end */);
+let xs = [
+  1, // one
+  // before two
+  2 /* two */
+];
+add(
+  a, // first
+  b
+);
+";
+        let expected = "let h = {\"a\": 1, /* mid */ 2: [1, 2]};
+let n = 1 + /* one */ 2 * f(/* x */ x /* end */);
+let xs = [
+    1, // one
+    // before two
+    2 /* two */
+];
+add(
+    a, // first
+    b
+);
+";
+
+        assert_eq!(format_ok(source), expected);
+    }
+
+    #[test]
+    fn test_format_keeps_comments_inside_brackets() {
+        let source = "if (a /* c */) { b }
+while (a // c
+) { b }
+for (x in xs /* c */) {}
+let y = (1 + 2 // c
+) * 3;
+let i = a[1 /* i */];
+";
+        let expected = "if (a /* c */) {
+    b;
+}
+while (a // c
+    ) {
+    b;
+}
+for (x in xs /* c */) {}
+let y = (1 + 2 // c
+    ) * 3;
+let i = a[1 /* i */];
+";
+
+        assert_eq!(format_ok(source), expected);
+    }
+
+    #[test]
+    fn test_format_spaces_around_comments() {
+        assert_eq!(
+            format_ok("let x = 1 /* mid */ + 2;"),
+            "let x = 1 /* mid */ + 2;\n"
+        );
+        assert_eq!(format_ok("x /* c */ = 1;"), "x /* c */ = 1;\n");
+        assert_eq!(
+            format_ok("let x = 1 + // c\n2;"),
+            "let x = 1 + // c\n    2;\n"
+        );
+        assert_eq!(format_ok("( // c\n x)"), "// c\nx;\n");
+        assert_eq!(
+            format_ok("if (a) {\n  ( // c\n  x)\n}"),
+            "if (a) {\n    // c\n    x;\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_format_is_idempotent_around_nested_comments() {
+        let sources = [
+            "( // c\n x)",
+            "let h = {\"a\": 1, true: fn() { x /* b */ }};",
+            "let h = {\"a\": 1, true: fn // l\n() { x }};",
+            "let f = fn(a, // l\n b) { g(-( // l\na // l\n), [b]) };",
+            "f(x, ( // l\ny));",
+            "let h = {2: [1, 2] // l\n, /* b */ // l\ntrue: 1};",
+        ];
+
+        for source in sources.iter() {
+            format_ok(source);
+        }
+    }
+
+    #[test]
+    fn test_format_keeps_comment_before_else() {
+        let source = "if (a) { b } // after if
+else { c }
+if (a) { b } /* then */ else /* otherwise */ { c }
+";
+        let expected = "if (a) {
+    b;
+} // after if
+else {
+    c;
+}
+if (a) {
+    b;
+} /* then */ else /* otherwise */ {
+    c;
+}
+";
+
+        assert_eq!(format_ok(source), expected);
+    }
+
+    #[test]
+    fn test_format_keeps_number_spelling() {
+        let source = "let k = 0xFF + 0b1010 + 1_000_000 + 1e-9 + 1.5e10;\n";
+
+        assert_eq!(format_ok(source), source);
+    }
+
+    #[test]
+    fn test_format_rejects_parse_errors() {
+        let errors = format("let x = ;\nlet y 2;").unwrap_err();
+        let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+
+        assert_eq!(
+            errors,
+            [
+                "line 1, column 9: no prefix parse function found for ;",
+                "line 2, column 7: expected = but got INT",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_formats_stdin() {
+        let args: Vec<String> = vec![];
+        let mut stdout: Vec<u8> = vec![];
+        let mut stderr: Vec<u8> = vec![];
+
+        let status = run(&args, "let x=1".as_bytes(), &mut stdout, &mut stderr);
+
+        assert_eq!(status, 0);
+        assert_eq!("let x = 1;\n", std::str::from_utf8(&stdout).unwrap());
+        assert!(stderr.is_empty());
+    }
+
+    #[test]
+    fn test_run_checks_stdin() {
+        let args = vec!["--check".to_string()];
+        let mut stdout: Vec<u8> = vec![];
+        let mut stderr: Vec<u8> = vec![];
+
+        let status = run(&args, "let x=1".as_bytes(), &mut stdout, &mut stderr);
+
+        assert_eq!(status, 1);
+        assert_eq!("<stdin>\n", std::str::from_utf8(&stdout).unwrap());
+        assert!(stderr.is_empty());
+
+        let mut stdout: Vec<u8> = vec![];
+
+        let status = run(&args, "let x = 1;\n".as_bytes(), &mut stdout, &mut stderr);
+
+        assert_eq!(status, 0);
+        assert!(stdout.is_empty());
+        assert!(stderr.is_empty());
+    }
+
+    #[test]
+    fn test_run_prints_parse_errors() {
+        let args: Vec<String> = vec![];
+        let mut stdout: Vec<u8> = vec![];
+        let mut stderr: Vec<u8> = vec![];
+
+        let status = run(&args, "let = 1".as_bytes(), &mut stdout, &mut stderr);
+
+        let expected = "<stdin>: line 1, column 5: expected IDENT but got =\n";
+
+        assert_eq!(status, 1);
+        assert!(stdout.is_empty());
+        assert_eq!(expected, std::str::from_utf8(&stderr).unwrap());
+    }
+
+    #[test]
+    fn test_run_rejects_bad_arguments() {
+        let args = vec!["--diff".to_string()];
+        let mut stdout: Vec<u8> = vec![];
+        let mut stderr: Vec<u8> = vec![];
+
+        assert_eq!(run(&args, "".as_bytes(), &mut stdout, &mut stderr), 2);
+    }
+
+    #[test]
+    fn test_run_files() {
+        let dir = std::env::temp_dir().join(format!("monkey-fmt-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let messy = dir.join("messy.mk");
+        let clean = dir.join("clean.mk");
+        std::fs::write(&messy, "let x=1 // one").unwrap();
+        std::fs::write(&clean, "let y = 2;\n").unwrap();
+        let messy_path = messy.to_str().unwrap().to_string();
+        let clean_path = clean.to_str().unwrap().to_string();
+
+        let args = vec![
+            "--check".to_string(),
+            messy_path.clone(),
+            clean_path.clone(),
+        ];
+        let mut stdout: Vec<u8> = vec![];
+        let mut stderr: Vec<u8> = vec![];
+
+        assert_eq!(run(&args, "".as_bytes(), &mut stdout, &mut stderr), 1);
+        assert_eq!(
+            format!("{}\n", messy_path),
+            std::str::from_utf8(&stdout).unwrap()
+        );
+        assert_eq!(std::fs::read_to_string(&messy).unwrap(), "let x=1 // one");
+
+        let write_args = vec![messy_path, clean_path];
+        let mut stdout: Vec<u8> = vec![];
+
+        assert_eq!(run(&write_args, "".as_bytes(), &mut stdout, &mut stderr), 0);
+        assert_eq!(
+            std::fs::read_to_string(&messy).unwrap(),
+            "let x = 1; // one\n"
+        );
+
+        let mut stdout: Vec<u8> = vec![];
+
+        assert_eq!(run(&args, "".as_bytes(), &mut stdout, &mut stderr), 0);
+        assert!(stdout.is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,398 @@
+use crate::token::TokenKind;
+use crate::{ast, lexer, parser, token};
+use std::fmt;
+use std::fs;
+use std::io;
+
+const USAGE: &str = "usage: monkey-lang --ast-json|--tokens-json [FILE]";
+
+// A JSON document built up from the AST before being written out compactly.
+enum Json {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Array(Vec<Json>),
+    Object(Vec<(&'static str, Json)>),
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(v) => write!(f, "{}", v),
+            Json::Int(v) => write!(f, "{}", v),
+            Json::Float(v) => write!(f, "{:?}", v),
+            Json::Str(v) => write_string(f, v),
+            Json::Array(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(fields) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter, value: &str) -> fmt::Result {
+    write!(f, "\"")?;
+
+    for ch in value.chars() {
+        match ch {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+
+    write!(f, "\"")
+}
+
+impl token::Token {
+    /// `{"type": <TokenKind variant>, "literal": ..., "span": ...}`.
+    pub fn to_json(&self) -> String {
+        token_json(self).to_string()
+    }
+}
+
+impl ast::Program {
+    /// Serialises the tree as JSON. Every node is an object tagged with its
+    /// struct name under `"type"` and carries the span of its token.
+    pub fn to_json(&self) -> String {
+        program_json(self).to_string()
+    }
+}
+
+impl ast::Statements {
+    pub fn to_json(&self) -> String {
+        statement_json(self).to_string()
+    }
+}
+
+impl ast::Expressions {
+    pub fn to_json(&self) -> String {
+        expression_json(self).to_string()
+    }
+}
+
+fn span_json(span: token::Span) -> Json {
+    Json::Object(vec![
+        ("start", Json::Int(span.start as i64)),
+        ("end", Json::Int(span.end as i64)),
+        ("line", Json::Int(span.line as i64)),
+        ("column", Json::Int(span.column as i64)),
+    ])
+}
+
+fn token_json(token: &token::Token) -> Json {
+    Json::Object(vec![
+        ("type", Json::Str(format!("{:?}", token.token_type))),
+        ("literal", Json::Str(token.literal.clone())),
+        ("span", span_json(token.span)),
+    ])
+}
+
+// Starts a node object with its `type` tag and the span of `token`.
+fn node(
+    node_type: &'static str,
+    token: &token::Token,
+    mut fields: Vec<(&'static str, Json)>,
+) -> Json {
+    let mut object = vec![
+        ("type", Json::Str(node_type.to_string())),
+        ("span", span_json(token.span)),
+    ];
+    object.append(&mut fields);
+    Json::Object(object)
+}
+
+fn program_json(program: &ast::Program) -> Json {
+    Json::Object(vec![
+        ("type", Json::Str("Program".to_string())),
+        (
+            "statements",
+            Json::Array(program.statements.iter().map(statement_json).collect()),
+        ),
+    ])
+}
+
+fn block_json(block: &ast::BlockStatement) -> Json {
+    node(
+        "BlockStatement",
+        &block.token,
+        vec![(
+            "statements",
+            Json::Array(block.statements.iter().map(statement_json).collect()),
+        )],
+    )
+}
+
+fn identifier_json(identifier: &ast::Identifier) -> Json {
+    node(
+        "Identifier",
+        &identifier.token,
+        vec![("value", Json::Str(identifier.value.clone()))],
+    )
+}
+
+fn statement_json(statement: &ast::Statements) -> Json {
+    match statement {
+        ast::Statements::Let(s) => node(
+            "LetStatement",
+            &s.token,
+            vec![
+                ("name", identifier_json(&s.name)),
+                ("value", expression_json(&s.value)),
+            ],
+        ),
+        ast::Statements::Return(s) => node(
+            "ReturnStatement",
+            &s.token,
+            vec![(
+                "return_value",
+                s.return_value.as_ref().map_or(Json::Null, expression_json),
+            )],
+        ),
+        ast::Statements::Expression(s) => node(
+            "ExpressionStatement",
+            &s.token,
+            vec![("expression", expression_json(&s.expression))],
+        ),
+        ast::Statements::While(s) => node(
+            "WhileStatement",
+            &s.token,
+            vec![
+                ("condition", expression_json(&s.condition)),
+                ("body", block_json(&s.body)),
+            ],
+        ),
+        ast::Statements::For(s) => node(
+            "ForStatement",
+            &s.token,
+            vec![
+                (
+                    "variables",
+                    Json::Array(s.variables.iter().map(identifier_json).collect()),
+                ),
+                ("iterable", expression_json(&s.iterable)),
+                ("body", block_json(&s.body)),
+            ],
+        ),
+        ast::Statements::Break(s) => node("BreakStatement", &s.token, vec![]),
+        ast::Statements::Continue(s) => node("ContinueStatement", &s.token, vec![]),
+    }
+}
+
+fn expression_json(expression: &ast::Expressions) -> Json {
+    match expression {
+        ast::Expressions::Identifier(i) => identifier_json(i),
+        ast::Expressions::IntegerLiteral(i) => node(
+            "IntegerLiteral",
+            &i.token,
+            vec![("value", Json::Int(i.value))],
+        ),
+        ast::Expressions::FloatLiteral(f) => node(
+            "FloatLiteral",
+            &f.token,
+            vec![("value", Json::Float(f.value))],
+        ),
+        ast::Expressions::StringLiteral(s) => node(
+            "StringLiteral",
+            &s.token,
+            vec![("value", Json::Str(s.value.clone()))],
+        ),
+        ast::Expressions::Boolean(b) => {
+            node("Boolean", &b.token, vec![("value", Json::Bool(b.value))])
+        }
+        ast::Expressions::Prefix(p) => node(
+            "PrefixExpression",
+            &p.token,
+            vec![
+                ("operator", Json::Str(p.operator.clone())),
+                ("right", expression_json(&p.right)),
+            ],
+        ),
+        ast::Expressions::Infix(i) => node(
+            "InfixExpression",
+            &i.token,
+            vec![
+                ("left", expression_json(&i.left)),
+                ("operator", Json::Str(i.operator.clone())),
+                ("right", expression_json(&i.right)),
+            ],
+        ),
+        ast::Expressions::Logical(l) => node(
+            "LogicalExpression",
+            &l.token,
+            vec![
+                ("left", expression_json(&l.left)),
+                ("operator", Json::Str(l.operator.clone())),
+                ("right", expression_json(&l.right)),
+            ],
+        ),
+        ast::Expressions::Assign(a) => node(
+            "AssignExpression",
+            &a.token,
+            vec![
+                ("target", expression_json(&a.target)),
+                ("operator", Json::Str(a.operator.clone())),
+                ("value", expression_json(&a.value)),
+            ],
+        ),
+        ast::Expressions::If(i) => node(
+            "IfStatement",
+            &i.token,
+            vec![
+                ("condition", expression_json(&i.condition)),
+                ("consequence", block_json(&i.consequence)),
+                (
+                    "alternative",
+                    i.alternative.as_ref().map_or(Json::Null, block_json),
+                ),
+            ],
+        ),
+        ast::Expressions::Function(f) => node(
+            "FunctionLiteral",
+            &f.token,
+            vec![
+                (
+                    "parameters",
+                    Json::Array(f.parameters.iter().map(identifier_json).collect()),
+                ),
+                ("body", block_json(&f.body)),
+            ],
+        ),
+        ast::Expressions::Call(c) => node(
+            "CallExpression",
+            &c.token,
+            vec![
+                ("function", expression_json(&c.function)),
+                (
+                    "arguments",
+                    Json::Array(c.arguments.iter().map(expression_json).collect()),
+                ),
+            ],
+        ),
+        ast::Expressions::Array(a) => node(
+            "ArrayLiteral",
+            &a.token,
+            vec![(
+                "elements",
+                Json::Array(a.elements.iter().map(expression_json).collect()),
+            )],
+        ),
+        ast::Expressions::Index(i) => node(
+            "IndexExpression",
+            &i.token,
+            vec![
+                ("left", expression_json(&i.left)),
+                ("index", expression_json(&i.index)),
+            ],
+        ),
+        ast::Expressions::Hash(h) => node(
+            "HashLiteral",
+            &h.token,
+            vec![(
+                "pairs",
+                Json::Array(
+                    h.pairs
+                        .iter()
+                        .map(|(key, value)| {
+                            Json::Object(vec![
+                                ("key", expression_json(key)),
+                                ("value", expression_json(value)),
+                            ])
+                        })
+                        .collect(),
+                ),
+            )],
+        ),
+    }
+}
+
+/// Runs the `--ast-json` or `--tokens-json` flag: prints the AST or the token
+/// stream (comments included) of a file, or of stdin when no file is given,
+/// as a single line of JSON. Source that does not parse prints its parse
+/// errors instead.
+pub fn run<R: io::Read, W: io::Write, E: io::Write>(
+    args: &[String],
+    mut stdin: R,
+    stdout: &mut W,
+    stderr: &mut E,
+) -> i32 {
+    let (flag, path) = match args {
+        [flag] => (flag.as_str(), None),
+        [flag, path] => (flag.as_str(), Some(path.as_str())),
+        _ => {
+            writeln!(stderr, "{}", USAGE).unwrap();
+            return 2;
+        }
+    };
+
+    let mut source = String::new();
+    let read = match path {
+        Some(path) => fs::read_to_string(path).map(|s| source = s),
+        None => stdin.read_to_string(&mut source).map(|_| ()),
+    };
+    let name = path.unwrap_or("<stdin>");
+    if let Err(error) = read {
+        writeln!(stderr, "{}: {}", name, error).unwrap();
+        return 1;
+    }
+
+    let json = match flag {
+        "--tokens-json" => {
+            let mut lexer = lexer::with_comments(source);
+            let mut tokens = vec![];
+            loop {
+                let token = lexer.next_token();
+                let eof = token.token_type == TokenKind::Eof;
+                tokens.push(token_json(&token));
+                if eof {
+                    break;
+                }
+            }
+            Json::Array(tokens)
+        }
+        "--ast-json" => {
+            let mut lexer = lexer::new(source);
+            let mut parser = parser::new(&mut lexer);
+            let program = parser.parse_program();
+
+            if !parser.errors().is_empty() {
+                for error in parser.errors() {
+                    writeln!(stderr, "{}: {}", name, error).unwrap();
+                }
+                return 1;
+            }
+            program_json(&program)
+        }
+        _ => {
+            writeln!(stderr, "{}", USAGE).unwrap();
+            return 2;
+        }
+    };
+
+    writeln!(stdout, "{}", json).unwrap();
+    0
+}
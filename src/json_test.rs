@@ -0,0 +1,157 @@
+#[cfg(test)]
+mod tests {
+    use crate::json::*;
+    use crate::{lexer, parser};
+
+    fn parse(input: &str) -> crate::ast::Program {
+        let mut lexer = lexer::new(input.to_string());
+        let mut parser = parser::new(&mut lexer);
+        let program = parser.parse_program();
+
+        assert!(parser.errors().is_empty(), "{}", input);
+        program
+    }
+
+    #[test]
+    fn test_token_to_json() {
+        let mut lexer = lexer::new("\"a\\\"b\\n\" x".to_string());
+
+        assert_eq!(
+            lexer.next_token().to_json(),
+            r#"{"type":"String","literal":"a\"b\n","span":{"start":0,"end":8,"line":1,"column":1}}"#
+        );
+        assert_eq!(
+            lexer.next_token().to_json(),
+            r#"{"type":"Ident","literal":"x","span":{"start":9,"end":10,"line":1,"column":10}}"#
+        );
+    }
+
+    #[test]
+    fn test_program_to_json() {
+        let program = parse("let x = -1;");
+
+        assert_eq!(
+            program.to_json(),
+            concat!(
+                r#"{"type":"Program","statements":[{"type":"LetStatement","#,
+                r#""span":{"start":0,"end":3,"line":1,"column":1},"#,
+                r#""name":{"type":"Identifier","span":{"start":4,"end":5,"line":1,"column":5},"value":"x"},"#,
+                r#""value":{"type":"PrefixExpression","span":{"start":8,"end":9,"line":1,"column":9},"#,
+                r#""operator":"-","right":{"type":"IntegerLiteral","#,
+                r#""span":{"start":9,"end":10,"line":1,"column":10},"value":1}}}]}"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_expression_to_json() {
+        let tests = [
+            (
+                "2.5",
+                r#"{"type":"FloatLiteral","span":{"start":0,"end":3,"line":1,"column":1},"value":2.5}"#,
+            ),
+            (
+                r#""\t\u{1}""#,
+                r#"{"type":"StringLiteral","span":{"start":0,"end":9,"line":1,"column":1},"value":"\t\u0001"}"#,
+            ),
+            (
+                "{true: []}",
+                concat!(
+                    r#"{"type":"HashLiteral","span":{"start":0,"end":1,"line":1,"column":1},"pairs":[{"#,
+                    r#""key":{"type":"Boolean","span":{"start":1,"end":5,"line":1,"column":2},"value":true},"#,
+                    r#""value":{"type":"ArrayLiteral","span":{"start":7,"end":8,"line":1,"column":8},"elements":[]}}]}"#
+                ),
+            ),
+        ];
+
+        for &test in tests.iter() {
+            let program = parse(test.0);
+
+            assert_eq!(program.statements[0].expression().to_json(), test.1);
+        }
+    }
+
+    #[test]
+    fn test_statement_to_json() {
+        let program = parse("if (a) { return; }");
+        let json = program.statements[0].to_json();
+
+        assert!(
+            json.starts_with(r#"{"type":"ExpressionStatement""#),
+            "{}",
+            json
+        );
+        assert!(json.contains(r#""type":"IfStatement""#), "{}", json);
+        assert!(
+            json.contains(r#""type":"ReturnStatement","span":{"start":9,"end":15,"line":1,"column":10},"return_value":null"#),
+            "{}",
+            json
+        );
+        assert!(json.ends_with(r#""alternative":null}}"#), "{}", json);
+    }
+
+    #[test]
+    fn test_run_tokens_json() {
+        let args = vec!["--tokens-json".to_string()];
+        let mut stdout: Vec<u8> = vec![];
+        let mut stderr: Vec<u8> = vec![];
+
+        let status = run(&args, "// c\n1".as_bytes(), &mut stdout, &mut stderr);
+
+        let expected = concat!(
+            r#"[{"type":"Comment","literal":"// c","span":{"start":0,"end":4,"line":1,"column":1}},"#,
+            r#"{"type":"Int","literal":"1","span":{"start":5,"end":6,"line":2,"column":1}},"#,
+            r#"{"type":"Eof","literal":"","span":{"start":6,"end":6,"line":2,"column":2}}]"#,
+            "\n"
+        );
+
+        assert_eq!(status, 0);
+        assert_eq!(expected, std::str::from_utf8(&stdout).unwrap());
+    }
+
+    #[test]
+    fn test_run_ast_json() {
+        let args = vec!["--ast-json".to_string()];
+        let mut stdout: Vec<u8> = vec![];
+        let mut stderr: Vec<u8> = vec![];
+
+        let status = run(&args, "x".as_bytes(), &mut stdout, &mut stderr);
+
+        let expected = format!("{}\n", parse("x").to_json());
+
+        assert_eq!(status, 0);
+        assert_eq!(expected, std::str::from_utf8(&stdout).unwrap());
+    }
+
+    #[test]
+    fn test_run_prints_parse_errors() {
+        let args = vec!["--ast-json".to_string()];
+        let mut stdout: Vec<u8> = vec![];
+        let mut stderr: Vec<u8> = vec![];
+
+        let status = run(&args, "let = 1".as_bytes(), &mut stdout, &mut stderr);
+
+        let expected = "<stdin>: line 1, column 5: expected IDENT but got =\n";
+
+        assert_eq!(status, 1);
+        assert!(stdout.is_empty());
+        assert_eq!(expected, std::str::from_utf8(&stderr).unwrap());
+    }
+
+    #[test]
+    fn test_run_rejects_bad_arguments() {
+        let args = vec!["--ast-json".to_string(), "a".to_string(), "b".to_string()];
+        let mut stdout: Vec<u8> = vec![];
+        let mut stderr: Vec<u8> = vec![];
+
+        assert_eq!(run(&args, "".as_bytes(), &mut stdout, &mut stderr), 2);
+
+        let args = vec!["--ast-json".to_string(), "/nonexistent/file.mk".to_string()];
+        let mut stderr: Vec<u8> = vec![];
+
+        assert_eq!(run(&args, "".as_bytes(), &mut stdout, &mut stderr), 1);
+        assert!(std::str::from_utf8(&stderr)
+            .unwrap()
+            .starts_with("/nonexistent/file.mk: "));
+    }
+}
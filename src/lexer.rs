@@ -1,10 +1,17 @@
 use crate::token;
+use crate::token::TokenKind;
 
+/// Walks the source with a byte cursor: `position` is the byte offset of `ch`
+/// and `read_position` the offset of the character after it. Comments are
+/// skipped unless the lexer was created with `with_comments`.
 pub struct Lexer {
     input: String,
     position: usize,
     read_position: usize,
     ch: char,
+    line: usize,
+    column: usize,
+    emit_comments: bool,
 }
 
 pub fn new(input: String) -> Lexer {
@@ -12,48 +19,89 @@ pub fn new(input: String) -> Lexer {
         input,
         position: 0,
         read_position: 0,
-        ch: '0',
+        ch: '\0',
+        line: 1,
+        column: 1,
+        emit_comments: false,
     };
     lex.read_char();
     lex
 }
 
+pub fn with_comments(input: String) -> Lexer {
+    let mut lex = new(input);
+    lex.emit_comments = true;
+    lex
+}
+
 impl Lexer {
-    #[allow(dead_code)]
     pub fn next_token(&mut self) -> token::Token {
-        self.skip_whitespace();
+        loop {
+            self.skip_whitespace();
+
+            let start = self.position;
+            let line = self.line;
+            let column = self.column;
+
+            let mut token = self.read_token();
+            token.span = token::Span {
+                start,
+                end: self.position,
+                line,
+                column,
+            };
+
+            if token.token_type != TokenKind::Comment || self.emit_comments {
+                return token;
+            }
+        }
+    }
+
+    fn read_token(&mut self) -> token::Token {
         let token = match self.ch {
-            '=' => {
-                if self.peek_ahead() == '=' {
-                    let ch = self.ch;
-                    self.read_char();
-                    token::new(token::EQ, ch.to_string() + &self.ch.to_string())
-                } else {
-                    token::new(token::ASSIGN, self.ch.to_string())
-                }
+            '=' => return self.read_operator(&[('=', TokenKind::Eq)], TokenKind::Assign),
+            '!' => return self.read_operator(&[('=', TokenKind::NotEq)], TokenKind::Bang),
+            '<' => {
+                return self.read_operator(
+                    &[('=', TokenKind::LtEq), ('<', TokenKind::ShiftLeft)],
+                    TokenKind::Lt,
+                )
             }
-            '!' => {
-                if self.peek_ahead() == '=' {
-                    let ch = self.ch;
-                    self.read_char();
-                    token::new(token::NOT_EQ, ch.to_string() + &self.ch.to_string())
-                } else {
-                    token::new(token::BANG, self.ch.to_string())
-                }
+            '>' => {
+                return self.read_operator(
+                    &[('=', TokenKind::GtEq), ('>', TokenKind::ShiftRight)],
+                    TokenKind::Gt,
+                )
             }
-            ';' => token::new(token::SEMICOLON, self.ch.to_string()),
-            '(' => token::new(token::LPAREN, self.ch.to_string()),
-            ')' => token::new(token::RPAREN, self.ch.to_string()),
-            ',' => token::new(token::COMMA, self.ch.to_string()),
-            '+' => token::new(token::PLUS, self.ch.to_string()),
-            '-' => token::new(token::MINUS, self.ch.to_string()),
-            '*' => token::new(token::ASTERISK, self.ch.to_string()),
-            '/' => token::new(token::SLASH, self.ch.to_string()),
-            '<' => token::new(token::LT, self.ch.to_string()),
-            '>' => token::new(token::GT, self.ch.to_string()),
-            '{' => token::new(token::LBRACE, self.ch.to_string()),
-            '}' => token::new(token::RBRACE, self.ch.to_string()),
-            '\0' => token::new(token::EOF, String::from("")),
+            '&' => return self.read_operator(&[('&', TokenKind::And)], TokenKind::Ampersand),
+            '|' => return self.read_operator(&[('|', TokenKind::Or)], TokenKind::Pipe),
+            '*' => {
+                return self.read_operator(
+                    &[('*', TokenKind::Power), ('=', TokenKind::AsteriskAssign)],
+                    TokenKind::Asterisk,
+                )
+            }
+            '+' => return self.read_operator(&[('=', TokenKind::PlusAssign)], TokenKind::Plus),
+            '-' => return self.read_operator(&[('=', TokenKind::MinusAssign)], TokenKind::Minus),
+            ';' => token::new(TokenKind::Semicolon, self.ch.to_string()),
+            ':' => token::new(TokenKind::Colon, self.ch.to_string()),
+            '(' => token::new(TokenKind::LParen, self.ch.to_string()),
+            ')' => token::new(TokenKind::RParen, self.ch.to_string()),
+            ',' => token::new(TokenKind::Comma, self.ch.to_string()),
+            '%' => token::new(TokenKind::Percent, self.ch.to_string()),
+            '^' => token::new(TokenKind::Caret, self.ch.to_string()),
+            '~' => token::new(TokenKind::Tilde, self.ch.to_string()),
+            '/' => match self.peek_ahead() {
+                '/' => return self.read_line_comment(),
+                '*' => return self.read_block_comment(),
+                _ => return self.read_operator(&[('=', TokenKind::SlashAssign)], TokenKind::Slash),
+            },
+            '{' => token::new(TokenKind::LBrace, self.ch.to_string()),
+            '}' => token::new(TokenKind::RBrace, self.ch.to_string()),
+            '[' => token::new(TokenKind::LBracket, self.ch.to_string()),
+            ']' => token::new(TokenKind::RBracket, self.ch.to_string()),
+            '"' => return self.read_string(),
+            '\0' => token::new(TokenKind::Eof, String::from("")),
             _ => {
                 if is_letter(self.ch) {
                     let identifier = self.read_identifier();
@@ -61,9 +109,9 @@ impl Lexer {
                     return token::new(token_type, identifier.to_string());
                 }
                 if is_digit(self.ch) {
-                    return token::new(token::INT, self.read_number().to_string());
+                    return self.read_number();
                 }
-                token::new(token::ILLEGAL, self.ch.to_string())
+                token::new(TokenKind::Illegal, self.ch.to_string())
             }
         };
 
@@ -72,14 +120,24 @@ impl Lexer {
     }
 
     fn read_char(&mut self) {
-        if self.read_position >= self.input.len() {
-            self.ch = '\0';
-        } else {
-            self.ch = self.input.chars().nth(self.read_position).unwrap();
+        if self.read_position > self.position {
+            if self.ch == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
         }
 
         self.position = self.read_position;
-        self.read_position += 1;
+
+        match self.input[self.read_position..].chars().next() {
+            Some(ch) => {
+                self.ch = ch;
+                self.read_position += ch.len_utf8();
+            }
+            None => self.ch = '\0',
+        }
     }
 
     fn read_identifier(&mut self) -> &str {
@@ -89,24 +147,225 @@ impl Lexer {
             self.read_char();
         }
 
-        self.input.get(position..self.position).unwrap()
+        &self.input[position..self.position]
     }
 
-    fn read_number(&mut self) -> &str {
+    fn read_number(&mut self) -> token::Token {
         let position = self.position;
-        while is_digit(self.ch) {
+        let mut token_type = TokenKind::Int;
+
+        // Radix-prefixed literals take every alphanumeric character that follows
+        // so that `0b102` reaches the parser whole and is reported as invalid.
+        if self.ch == '0' && matches!(self.peek_ahead(), 'x' | 'X' | 'b' | 'B' | 'o' | 'O') {
+            self.read_char();
             self.read_char();
+            while self.ch.is_ascii_alphanumeric() || self.ch == '_' {
+                self.read_char();
+            }
+
+            return token::new(token_type, self.input[position..self.position].to_string());
         }
 
-        self.input.get(position..self.position).unwrap()
+        self.read_digits();
+
+        if self.ch == '.' && is_digit(self.peek_ahead()) {
+            token_type = TokenKind::Float;
+            self.read_char();
+            self.read_digits();
+        }
+
+        if self.ch == 'e' || self.ch == 'E' {
+            let sign = self.peek_ahead() == '+' || self.peek_ahead() == '-';
+            let digit = if sign {
+                self.peek_nth(1)
+            } else {
+                self.peek_ahead()
+            };
+
+            if is_digit(digit) {
+                token_type = TokenKind::Float;
+                self.read_char();
+                if sign {
+                    self.read_char();
+                }
+                self.read_digits();
+            }
+        }
+
+        token::new(token_type, self.input[position..self.position].to_string())
     }
 
-    fn peek_ahead(&self) -> char {
-        if self.read_position >= self.input.len() {
-            return '\0';
+    fn read_digits(&mut self) {
+        while is_digit(self.ch) || self.ch == '_' {
+            self.read_char();
+        }
+    }
+
+    // Lexes `ch` followed by one of the `pairs` characters as the paired kind,
+    // or `ch` on its own as `single`.
+    fn read_operator(&mut self, pairs: &[(char, TokenKind)], single: TokenKind) -> token::Token {
+        let position = self.position;
+        let mut token_type = single;
+
+        if let Some(&(_, double)) = pairs.iter().find(|(ch, _)| *ch == self.peek_ahead()) {
+            self.read_char();
+            token_type = double;
+        }
+        self.read_char();
+
+        token::new(token_type, self.input[position..self.position].to_string())
+    }
+
+    fn read_line_comment(&mut self) -> token::Token {
+        let position = self.position;
+
+        while self.ch != '\n' && self.position < self.input.len() {
+            self.read_char();
+        }
+
+        token::new(
+            TokenKind::Comment,
+            self.input[position..self.position].to_string(),
+        )
+    }
+
+    fn read_block_comment(&mut self) -> token::Token {
+        let position = self.position;
+        let mut depth = 0;
+
+        loop {
+            if self.position >= self.input.len() {
+                return token::new(
+                    TokenKind::UnterminatedComment,
+                    self.input[position..].to_string(),
+                );
+            }
+
+            if self.ch == '/' && self.peek_ahead() == '*' {
+                depth += 1;
+                self.read_char();
+            } else if self.ch == '*' && self.peek_ahead() == '/' {
+                depth -= 1;
+                self.read_char();
+                if depth == 0 {
+                    self.read_char();
+                    break;
+                }
+            }
+
+            self.read_char();
         }
 
-        self.input.chars().nth(self.read_position).unwrap()
+        token::new(
+            TokenKind::Comment,
+            self.input[position..self.position].to_string(),
+        )
+    }
+
+    fn read_string(&mut self) -> token::Token {
+        let position = self.position;
+        let mut value = String::new();
+        let mut invalid_escape = None;
+
+        self.read_char();
+
+        while self.ch != '"' {
+            if self.position >= self.input.len() {
+                return token::new(
+                    TokenKind::UnterminatedString,
+                    self.input[position..].to_string(),
+                );
+            }
+
+            if self.ch == '\\' {
+                let escape_position = self.position;
+                match self.read_escape() {
+                    Some(ch) => value.push(ch),
+                    None => {
+                        if invalid_escape.is_none() {
+                            invalid_escape =
+                                Some(self.input[escape_position..self.position].to_string());
+                        }
+                        continue;
+                    }
+                }
+            } else {
+                value.push(self.ch);
+            }
+
+            self.read_char();
+        }
+
+        self.read_char();
+
+        match invalid_escape {
+            Some(escape) => token::new(TokenKind::Illegal, escape),
+            None => token::new(TokenKind::String, value),
+        }
+    }
+
+    // Reads the escape sequence starting at the current backslash, leaving the
+    // cursor on its last character. On failure the cursor is left on the first
+    // character that is not part of the sequence.
+    fn read_escape(&mut self) -> Option<char> {
+        self.read_char();
+
+        match self.ch {
+            'n' => Some('\n'),
+            't' => Some('\t'),
+            '"' => Some('"'),
+            '\\' => Some('\\'),
+            'u' => {
+                if self.peek_ahead() != '{' {
+                    self.read_char();
+                    return None;
+                }
+                self.read_char();
+                self.read_char();
+
+                let position = self.position;
+                while self.ch.is_ascii_hexdigit() {
+                    self.read_char();
+                }
+
+                if self.ch != '}' {
+                    return None;
+                }
+
+                let digits = &self.input[position..self.position];
+                let ch = if digits.is_empty() || digits.len() > 6 {
+                    None
+                } else {
+                    u32::from_str_radix(digits, 16)
+                        .ok()
+                        .and_then(std::char::from_u32)
+                };
+
+                if ch.is_none() {
+                    self.read_char();
+                }
+                ch
+            }
+            '\0' if self.position >= self.input.len() => None,
+            _ => {
+                self.read_char();
+                None
+            }
+        }
+    }
+
+    fn peek_nth(&self, n: usize) -> char {
+        self.input[self.read_position..]
+            .chars()
+            .nth(n)
+            .unwrap_or('\0')
+    }
+
+    fn peek_ahead(&self) -> char {
+        self.input[self.read_position..]
+            .chars()
+            .next()
+            .unwrap_or('\0')
     }
 
     fn skip_whitespace(&mut self) {
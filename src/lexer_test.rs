@@ -2,6 +2,7 @@
 mod tests {
     use crate::lexer::*;
     use crate::token;
+    use crate::token::TokenKind;
 
     #[test]
     fn test_next_token() {
@@ -14,7 +15,7 @@ mod tests {
            };
            let result = add(five, ten);
 
-           !-/*5;
+           !-/ *5;
            5 < 10 > 5;
 
            if (5 < 10) {
@@ -29,80 +30,80 @@ mod tests {
         );
 
         let tests = [
-            token::new(token::LET, "let".to_string()),
-            token::new(token::IDENT, "five".to_string()),
-            token::new(token::ASSIGN, "=".to_string()),
-            token::new(token::INT, "5".to_string()),
-            token::new(token::SEMICOLON, ";".to_string()),
-            token::new(token::LET, "let".to_string()),
-            token::new(token::IDENT, "ten".to_string()),
-            token::new(token::ASSIGN, "=".to_string()),
-            token::new(token::INT, "10".to_string()),
-            token::new(token::SEMICOLON, ";".to_string()),
-            token::new(token::LET, "let".to_string()),
-            token::new(token::IDENT, "add".to_string()),
-            token::new(token::ASSIGN, "=".to_string()),
-            token::new(token::FUNCTION, "fn".to_string()),
-            token::new(token::LPAREN, "(".to_string()),
-            token::new(token::IDENT, "x".to_string()),
-            token::new(token::COMMA, ",".to_string()),
-            token::new(token::IDENT, "y".to_string()),
-            token::new(token::RPAREN, ")".to_string()),
-            token::new(token::LBRACE, "{".to_string()),
-            token::new(token::IDENT, "x".to_string()),
-            token::new(token::PLUS, "+".to_string()),
-            token::new(token::IDENT, "y".to_string()),
-            token::new(token::SEMICOLON, ";".to_string()),
-            token::new(token::RBRACE, "}".to_string()),
-            token::new(token::SEMICOLON, ";".to_string()),
-            token::new(token::LET, "let".to_string()),
-            token::new(token::IDENT, "result".to_string()),
-            token::new(token::ASSIGN, "=".to_string()),
-            token::new(token::IDENT, "add".to_string()),
-            token::new(token::LPAREN, "(".to_string()),
-            token::new(token::IDENT, "five".to_string()),
-            token::new(token::COMMA, ",".to_string()),
-            token::new(token::IDENT, "ten".to_string()),
-            token::new(token::RPAREN, ")".to_string()),
-            token::new(token::SEMICOLON, ";".to_string()),
-            token::new(token::BANG, "!".to_string()),
-            token::new(token::MINUS, "-".to_string()),
-            token::new(token::SLASH, "/".to_string()),
-            token::new(token::ASTERISK, "*".to_string()),
-            token::new(token::INT, "5".to_string()),
-            token::new(token::SEMICOLON, ";".to_string()),
-            token::new(token::INT, "5".to_string()),
-            token::new(token::LT, "<".to_string()),
-            token::new(token::INT, "10".to_string()),
-            token::new(token::GT, ">".to_string()),
-            token::new(token::INT, "5".to_string()),
-            token::new(token::SEMICOLON, ";".to_string()),
-            token::new(token::IF, "if".to_string()),
-            token::new(token::LPAREN, "(".to_string()),
-            token::new(token::INT, "5".to_string()),
-            token::new(token::LT, "<".to_string()),
-            token::new(token::INT, "10".to_string()),
-            token::new(token::RPAREN, ")".to_string()),
-            token::new(token::LBRACE, "{".to_string()),
-            token::new(token::RETURN, "return".to_string()),
-            token::new(token::TRUE, "true".to_string()),
-            token::new(token::SEMICOLON, ";".to_string()),
-            token::new(token::RBRACE, "}".to_string()),
-            token::new(token::ELSE, "else".to_string()),
-            token::new(token::LBRACE, "{".to_string()),
-            token::new(token::RETURN, "return".to_string()),
-            token::new(token::FALSE, "false".to_string()),
-            token::new(token::SEMICOLON, ";".to_string()),
-            token::new(token::RBRACE, "}".to_string()),
-            token::new(token::INT, "10".to_string()),
-            token::new(token::EQ, "==".to_string()),
-            token::new(token::INT, "10".to_string()),
-            token::new(token::SEMICOLON, ";".to_string()),
-            token::new(token::INT, "10".to_string()),
-            token::new(token::NOT_EQ, "!=".to_string()),
-            token::new(token::INT, "9".to_string()),
-            token::new(token::SEMICOLON, ";".to_string()),
-            token::new(token::EOF, "".to_string()),
+            token::new(TokenKind::Let, "let".to_string()),
+            token::new(TokenKind::Ident, "five".to_string()),
+            token::new(TokenKind::Assign, "=".to_string()),
+            token::new(TokenKind::Int, "5".to_string()),
+            token::new(TokenKind::Semicolon, ";".to_string()),
+            token::new(TokenKind::Let, "let".to_string()),
+            token::new(TokenKind::Ident, "ten".to_string()),
+            token::new(TokenKind::Assign, "=".to_string()),
+            token::new(TokenKind::Int, "10".to_string()),
+            token::new(TokenKind::Semicolon, ";".to_string()),
+            token::new(TokenKind::Let, "let".to_string()),
+            token::new(TokenKind::Ident, "add".to_string()),
+            token::new(TokenKind::Assign, "=".to_string()),
+            token::new(TokenKind::Function, "fn".to_string()),
+            token::new(TokenKind::LParen, "(".to_string()),
+            token::new(TokenKind::Ident, "x".to_string()),
+            token::new(TokenKind::Comma, ",".to_string()),
+            token::new(TokenKind::Ident, "y".to_string()),
+            token::new(TokenKind::RParen, ")".to_string()),
+            token::new(TokenKind::LBrace, "{".to_string()),
+            token::new(TokenKind::Ident, "x".to_string()),
+            token::new(TokenKind::Plus, "+".to_string()),
+            token::new(TokenKind::Ident, "y".to_string()),
+            token::new(TokenKind::Semicolon, ";".to_string()),
+            token::new(TokenKind::RBrace, "}".to_string()),
+            token::new(TokenKind::Semicolon, ";".to_string()),
+            token::new(TokenKind::Let, "let".to_string()),
+            token::new(TokenKind::Ident, "result".to_string()),
+            token::new(TokenKind::Assign, "=".to_string()),
+            token::new(TokenKind::Ident, "add".to_string()),
+            token::new(TokenKind::LParen, "(".to_string()),
+            token::new(TokenKind::Ident, "five".to_string()),
+            token::new(TokenKind::Comma, ",".to_string()),
+            token::new(TokenKind::Ident, "ten".to_string()),
+            token::new(TokenKind::RParen, ")".to_string()),
+            token::new(TokenKind::Semicolon, ";".to_string()),
+            token::new(TokenKind::Bang, "!".to_string()),
+            token::new(TokenKind::Minus, "-".to_string()),
+            token::new(TokenKind::Slash, "/".to_string()),
+            token::new(TokenKind::Asterisk, "*".to_string()),
+            token::new(TokenKind::Int, "5".to_string()),
+            token::new(TokenKind::Semicolon, ";".to_string()),
+            token::new(TokenKind::Int, "5".to_string()),
+            token::new(TokenKind::Lt, "<".to_string()),
+            token::new(TokenKind::Int, "10".to_string()),
+            token::new(TokenKind::Gt, ">".to_string()),
+            token::new(TokenKind::Int, "5".to_string()),
+            token::new(TokenKind::Semicolon, ";".to_string()),
+            token::new(TokenKind::If, "if".to_string()),
+            token::new(TokenKind::LParen, "(".to_string()),
+            token::new(TokenKind::Int, "5".to_string()),
+            token::new(TokenKind::Lt, "<".to_string()),
+            token::new(TokenKind::Int, "10".to_string()),
+            token::new(TokenKind::RParen, ")".to_string()),
+            token::new(TokenKind::LBrace, "{".to_string()),
+            token::new(TokenKind::Return, "return".to_string()),
+            token::new(TokenKind::True, "true".to_string()),
+            token::new(TokenKind::Semicolon, ";".to_string()),
+            token::new(TokenKind::RBrace, "}".to_string()),
+            token::new(TokenKind::Else, "else".to_string()),
+            token::new(TokenKind::LBrace, "{".to_string()),
+            token::new(TokenKind::Return, "return".to_string()),
+            token::new(TokenKind::False, "false".to_string()),
+            token::new(TokenKind::Semicolon, ";".to_string()),
+            token::new(TokenKind::RBrace, "}".to_string()),
+            token::new(TokenKind::Int, "10".to_string()),
+            token::new(TokenKind::Eq, "==".to_string()),
+            token::new(TokenKind::Int, "10".to_string()),
+            token::new(TokenKind::Semicolon, ";".to_string()),
+            token::new(TokenKind::Int, "10".to_string()),
+            token::new(TokenKind::NotEq, "!=".to_string()),
+            token::new(TokenKind::Int, "9".to_string()),
+            token::new(TokenKind::Semicolon, ";".to_string()),
+            token::new(TokenKind::Eof, "".to_string()),
         ];
 
         let mut lexer = new(input);
@@ -112,4 +113,443 @@ mod tests {
             assert_eq!(token.token_type, test.token_type);
         }
     }
+
+    #[test]
+    fn test_token_spans() {
+        let input = String::from("let five = 5;\n  five == 10;");
+
+        let tests = [
+            (TokenKind::Let, 0, 3, 1, 1),
+            (TokenKind::Ident, 4, 8, 1, 5),
+            (TokenKind::Assign, 9, 10, 1, 10),
+            (TokenKind::Int, 11, 12, 1, 12),
+            (TokenKind::Semicolon, 12, 13, 1, 13),
+            (TokenKind::Ident, 16, 20, 2, 3),
+            (TokenKind::Eq, 21, 23, 2, 8),
+            (TokenKind::Int, 24, 26, 2, 11),
+            (TokenKind::Semicolon, 26, 27, 2, 13),
+            (TokenKind::Eof, 27, 27, 2, 14),
+        ];
+
+        let mut lexer = new(input);
+
+        for test in tests.iter() {
+            let token = lexer.next_token();
+            assert_eq!(token.token_type, test.0);
+            assert_eq!(
+                token.span,
+                token::Span {
+                    start: test.1,
+                    end: test.2,
+                    line: test.3,
+                    column: test.4,
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn test_token_kind_display() {
+        let tests = [
+            (TokenKind::Comma, ","),
+            (TokenKind::Ident, "IDENT"),
+            (TokenKind::NotEq, "!="),
+            (TokenKind::Function, "FUNCTION"),
+        ];
+
+        for test in tests.iter() {
+            assert_eq!(test.0.to_string(), test.1);
+        }
+
+        assert_eq!(token::lookup_identifier("fn"), TokenKind::Function);
+        assert_eq!(token::lookup_identifier("while"), TokenKind::While);
+        assert_eq!(token::lookup_identifier("for"), TokenKind::For);
+        assert_eq!(token::lookup_identifier("in"), TokenKind::In);
+        assert_eq!(token::lookup_identifier("break"), TokenKind::Break);
+        assert_eq!(token::lookup_identifier("continue"), TokenKind::Continue);
+        assert_eq!(token::lookup_identifier("fun"), TokenKind::Ident);
+    }
+
+    #[test]
+    fn test_non_ascii_input() {
+        let input = String::from("é + 1;\n  ünï == 2");
+
+        let tests = [
+            (TokenKind::Illegal, "é", 0, 2, 1, 1),
+            (TokenKind::Plus, "+", 3, 4, 1, 3),
+            (TokenKind::Int, "1", 5, 6, 1, 5),
+            (TokenKind::Semicolon, ";", 6, 7, 1, 6),
+            (TokenKind::Illegal, "ü", 10, 12, 2, 3),
+            (TokenKind::Ident, "n", 12, 13, 2, 4),
+            (TokenKind::Illegal, "ï", 13, 15, 2, 5),
+            (TokenKind::Eq, "==", 16, 18, 2, 7),
+            (TokenKind::Int, "2", 19, 20, 2, 10),
+            (TokenKind::Eof, "", 20, 20, 2, 11),
+        ];
+
+        let mut lexer = new(input);
+
+        for test in tests.iter() {
+            let token = lexer.next_token();
+            assert_eq!(token.token_type, test.0);
+            assert_eq!(token.literal, test.1);
+            assert_eq!(token.span.start, test.2);
+            assert_eq!(token.span.end, test.3);
+            assert_eq!(token.span.line, test.4);
+            assert_eq!(token.span.column, test.5);
+        }
+    }
+
+    #[test]
+    fn test_large_input() {
+        let input = "let value = (x + 10) * y;\n".repeat(100_000);
+
+        let mut lexer = new(input);
+        let mut count = 0;
+
+        loop {
+            let token = lexer.next_token();
+            if token.token_type == TokenKind::Eof {
+                break;
+            }
+            count += 1;
+        }
+
+        assert_eq!(count, 1_100_000);
+    }
+
+    #[test]
+    fn test_string_literals() {
+        let input =
+            String::from(r#""foobar" "foo bar" "a\nb\t\"c\"\\" "\u{1F600}\u{e9}" "" "héllo""#);
+
+        let tests = [
+            (TokenKind::String, "foobar"),
+            (TokenKind::String, "foo bar"),
+            (TokenKind::String, "a\nb\t\"c\"\\"),
+            (TokenKind::String, "\u{1F600}\u{e9}"),
+            (TokenKind::String, ""),
+            (TokenKind::String, "héllo"),
+            (TokenKind::Eof, ""),
+        ];
+
+        let mut lexer = new(input);
+
+        for test in tests.iter() {
+            let token = lexer.next_token();
+            assert_eq!(token.token_type, test.0);
+            assert_eq!(token.literal, test.1);
+        }
+    }
+
+    #[test]
+    fn test_invalid_string_escapes() {
+        let tests = [
+            (r#""a\qb" 1"#, r"\q"),
+            (r#""\u{zz}" 1"#, r"\u{"),
+            (r#""\u{110000}" 1"#, r"\u{110000}"),
+            (r#""\u{}" 1"#, r"\u{}"),
+            (r#""\u00e9" 1"#, r"\u"),
+        ];
+
+        for test in tests.iter() {
+            let mut lexer = new(test.0.to_string());
+
+            let token = lexer.next_token();
+            assert_eq!(token.token_type, TokenKind::Illegal);
+            assert_eq!(token.literal, test.1);
+            assert_eq!(token.span.start, 0);
+
+            let token = lexer.next_token();
+            assert_eq!(token.token_type, TokenKind::Int, "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_unterminated_string() {
+        let input = String::from("let s = \"abc\ndef");
+
+        let mut lexer = new(input);
+
+        for _ in 0..3 {
+            lexer.next_token();
+        }
+
+        let token = lexer.next_token();
+        assert_eq!(token.token_type, TokenKind::UnterminatedString);
+        assert_eq!(token.literal, "\"abc\ndef");
+        assert_eq!(token.span.start, 8);
+        assert_eq!(token.span.line, 1);
+        assert_eq!(token.span.column, 9);
+
+        assert_eq!(lexer.next_token().token_type, TokenKind::Eof);
+    }
+
+    #[test]
+    fn test_brackets() {
+        let input = String::from("[1, 2][0] {\"a\": 1}");
+
+        let tests = [
+            TokenKind::LBracket,
+            TokenKind::Int,
+            TokenKind::Comma,
+            TokenKind::Int,
+            TokenKind::RBracket,
+            TokenKind::LBracket,
+            TokenKind::Int,
+            TokenKind::RBracket,
+            TokenKind::LBrace,
+            TokenKind::String,
+            TokenKind::Colon,
+            TokenKind::Int,
+            TokenKind::RBrace,
+            TokenKind::Eof,
+        ];
+
+        let mut lexer = new(input);
+
+        for test in tests.iter() {
+            assert_eq!(lexer.next_token().token_type, *test);
+        }
+    }
+
+    #[test]
+    fn test_comments_are_skipped() {
+        let input = String::from(
+            r#"
+           // a line comment
+           let x = 10 / 2; // trailing
+           /* block
+              /* nested */ still comment */
+           x /**/ * 2
+           // comment at end of file"#,
+        );
+
+        let tests = [
+            TokenKind::Let,
+            TokenKind::Ident,
+            TokenKind::Assign,
+            TokenKind::Int,
+            TokenKind::Slash,
+            TokenKind::Int,
+            TokenKind::Semicolon,
+            TokenKind::Ident,
+            TokenKind::Asterisk,
+            TokenKind::Int,
+            TokenKind::Eof,
+        ];
+
+        let mut lexer = new(input);
+
+        for test in tests.iter() {
+            assert_eq!(lexer.next_token().token_type, *test);
+        }
+    }
+
+    #[test]
+    fn test_comments_as_trivia() {
+        let input = String::from("let x = 1; // one\n/* two /* three */ */ x");
+
+        let tests = [
+            (TokenKind::Let, "let"),
+            (TokenKind::Ident, "x"),
+            (TokenKind::Assign, "="),
+            (TokenKind::Int, "1"),
+            (TokenKind::Semicolon, ";"),
+            (TokenKind::Comment, "// one"),
+            (TokenKind::Comment, "/* two /* three */ */"),
+            (TokenKind::Ident, "x"),
+            (TokenKind::Eof, ""),
+        ];
+
+        let mut lexer = with_comments(input);
+
+        for test in tests.iter() {
+            let token = lexer.next_token();
+            assert_eq!(token.token_type, test.0);
+            assert_eq!(token.literal, test.1);
+        }
+    }
+
+    #[test]
+    fn test_unterminated_block_comment() {
+        let input = String::from("1 /* open /* nested */");
+
+        let mut lexer = new(input);
+
+        assert_eq!(lexer.next_token().token_type, TokenKind::Int);
+
+        let token = lexer.next_token();
+        assert_eq!(token.token_type, TokenKind::UnterminatedComment);
+        assert_eq!(token.span.start, 2);
+        assert_eq!(lexer.next_token().token_type, TokenKind::Eof);
+    }
+
+    #[test]
+    fn test_comparison_and_logical_operators() {
+        let input = String::from("a <= b >= c && d || e < f > g & h | i ^ j");
+
+        let tests = [
+            (TokenKind::Ident, "a"),
+            (TokenKind::LtEq, "<="),
+            (TokenKind::Ident, "b"),
+            (TokenKind::GtEq, ">="),
+            (TokenKind::Ident, "c"),
+            (TokenKind::And, "&&"),
+            (TokenKind::Ident, "d"),
+            (TokenKind::Or, "||"),
+            (TokenKind::Ident, "e"),
+            (TokenKind::Lt, "<"),
+            (TokenKind::Ident, "f"),
+            (TokenKind::Gt, ">"),
+            (TokenKind::Ident, "g"),
+            (TokenKind::Ampersand, "&"),
+            (TokenKind::Ident, "h"),
+            (TokenKind::Pipe, "|"),
+            (TokenKind::Ident, "i"),
+            (TokenKind::Caret, "^"),
+            (TokenKind::Ident, "j"),
+            (TokenKind::Eof, ""),
+        ];
+
+        let mut lexer = new(input);
+
+        for test in tests.iter() {
+            let token = lexer.next_token();
+            assert_eq!(token.token_type, test.0);
+            assert_eq!(token.literal, test.1);
+        }
+    }
+
+    #[test]
+    fn test_arithmetic_and_bitwise_operators() {
+        let input = String::from("a % b ** c * d << e >> f ~g <<= >>>");
+
+        let tests = [
+            (TokenKind::Ident, "a"),
+            (TokenKind::Percent, "%"),
+            (TokenKind::Ident, "b"),
+            (TokenKind::Power, "**"),
+            (TokenKind::Ident, "c"),
+            (TokenKind::Asterisk, "*"),
+            (TokenKind::Ident, "d"),
+            (TokenKind::ShiftLeft, "<<"),
+            (TokenKind::Ident, "e"),
+            (TokenKind::ShiftRight, ">>"),
+            (TokenKind::Ident, "f"),
+            (TokenKind::Tilde, "~"),
+            (TokenKind::Ident, "g"),
+            (TokenKind::ShiftLeft, "<<"),
+            (TokenKind::Assign, "="),
+            (TokenKind::ShiftRight, ">>"),
+            (TokenKind::Gt, ">"),
+            (TokenKind::Eof, ""),
+        ];
+
+        let mut lexer = new(input);
+
+        for test in tests.iter() {
+            let token = lexer.next_token();
+            assert_eq!(token.token_type, test.0);
+            assert_eq!(token.literal, test.1);
+        }
+    }
+
+    #[test]
+    fn test_assignment_operators() {
+        let input = String::from("x = 1; x += y -= z *= w /= 2 **= 3 + -4");
+
+        let tests = [
+            (TokenKind::Ident, "x"),
+            (TokenKind::Assign, "="),
+            (TokenKind::Int, "1"),
+            (TokenKind::Semicolon, ";"),
+            (TokenKind::Ident, "x"),
+            (TokenKind::PlusAssign, "+="),
+            (TokenKind::Ident, "y"),
+            (TokenKind::MinusAssign, "-="),
+            (TokenKind::Ident, "z"),
+            (TokenKind::AsteriskAssign, "*="),
+            (TokenKind::Ident, "w"),
+            (TokenKind::SlashAssign, "/="),
+            (TokenKind::Int, "2"),
+            (TokenKind::Power, "**"),
+            (TokenKind::Assign, "="),
+            (TokenKind::Int, "3"),
+            (TokenKind::Plus, "+"),
+            (TokenKind::Minus, "-"),
+            (TokenKind::Int, "4"),
+            (TokenKind::Eof, ""),
+        ];
+
+        let mut lexer = new(input);
+
+        for test in tests.iter() {
+            let token = lexer.next_token();
+            assert_eq!(token.token_type, test.0);
+            assert_eq!(token.literal, test.1);
+        }
+    }
+
+    #[test]
+    fn test_numbers() {
+        let input = String::from("5 3.14 0.5 1e-9 2.5E+3 7e2 1.foo 2e 3e+x 4..5");
+
+        let tests = [
+            (TokenKind::Int, "5"),
+            (TokenKind::Float, "3.14"),
+            (TokenKind::Float, "0.5"),
+            (TokenKind::Float, "1e-9"),
+            (TokenKind::Float, "2.5E+3"),
+            (TokenKind::Float, "7e2"),
+            (TokenKind::Int, "1"),
+            (TokenKind::Illegal, "."),
+            (TokenKind::Ident, "foo"),
+            (TokenKind::Int, "2"),
+            (TokenKind::Ident, "e"),
+            (TokenKind::Int, "3"),
+            (TokenKind::Ident, "e"),
+            (TokenKind::Plus, "+"),
+            (TokenKind::Ident, "x"),
+            (TokenKind::Int, "4"),
+            (TokenKind::Illegal, "."),
+            (TokenKind::Illegal, "."),
+            (TokenKind::Int, "5"),
+            (TokenKind::Eof, ""),
+        ];
+
+        let mut lexer = new(input);
+
+        for test in tests.iter() {
+            let token = lexer.next_token();
+            assert_eq!(token.token_type, test.0);
+            assert_eq!(token.literal, test.1);
+        }
+    }
+
+    #[test]
+    fn test_radix_and_underscore_numbers() {
+        let input = String::from("0xFF 0b1010 0o755 1_000_000 0XdeadBEEF 0b102 0x 1_000.000_1 007");
+
+        let tests = [
+            (TokenKind::Int, "0xFF"),
+            (TokenKind::Int, "0b1010"),
+            (TokenKind::Int, "0o755"),
+            (TokenKind::Int, "1_000_000"),
+            (TokenKind::Int, "0XdeadBEEF"),
+            (TokenKind::Int, "0b102"),
+            (TokenKind::Int, "0x"),
+            (TokenKind::Float, "1_000.000_1"),
+            (TokenKind::Int, "007"),
+            (TokenKind::Eof, ""),
+        ];
+
+        let mut lexer = new(input);
+
+        for test in tests.iter() {
+            let token = lexer.next_token();
+            assert_eq!(token.token_type, test.0);
+            assert_eq!(token.literal, test.1);
+        }
+    }
 }
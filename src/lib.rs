@@ -1,12 +1,26 @@
-mod ast;
-mod lexer;
-mod parser;
+pub mod ast;
+pub mod environment;
+pub mod eval;
+pub mod formatter;
+pub mod json;
+pub mod lexer;
+pub mod object;
+pub mod parser;
+pub mod pretty;
 pub mod repl;
-mod token;
+pub mod token;
 
 #[cfg(test)]
 mod ast_test;
 #[cfg(test)]
+mod eval_test;
+#[cfg(test)]
+mod formatter_test;
+#[cfg(test)]
+mod json_test;
+#[cfg(test)]
 mod lexer_test;
 #[cfg(test)]
 mod parser_test;
+#[cfg(test)]
+mod pretty_test;
@@ -1,7 +1,21 @@
-use monkey_lang::repl;
+use monkey_lang::{formatter, json, repl};
 
 fn main() {
-    loop {
-        repl::start(std::io::stdin(), &mut std::io::stdout());
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    match args.first().map(String::as_str) {
+        Some("fmt") => std::process::exit(formatter::run(
+            &args[1..],
+            std::io::stdin(),
+            &mut std::io::stdout(),
+            &mut std::io::stderr(),
+        )),
+        Some("--ast-json") | Some("--tokens-json") => std::process::exit(json::run(
+            &args,
+            std::io::stdin(),
+            &mut std::io::stdout(),
+            &mut std::io::stderr(),
+        )),
+        _ => repl::start(std::io::stdin(), &mut std::io::stdout()),
     }
 }
@@ -0,0 +1,110 @@
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum HashKey {
+    Integer(i64),
+    Boolean(bool),
+    String(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Object {
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    String(String),
+    Array(Vec<Object>),
+    Hash(BTreeMap<HashKey, Object>),
+    Null,
+    ReturnValue(Box<Object>),
+    Break,
+    Continue,
+    Error(String),
+}
+
+impl Object {
+    pub fn inspect(&self) -> String {
+        match self {
+            Object::Integer(v) => v.to_string(),
+            Object::Float(v) => format!("{:?}", v),
+            Object::Boolean(v) => v.to_string(),
+            Object::String(v) => v.clone(),
+            Object::Array(elements) => {
+                let elements: Vec<String> = elements.iter().map(|e| e.inspect()).collect();
+                format!("[{}]", elements.join(", "))
+            }
+            Object::Hash(pairs) => {
+                let pairs: Vec<String> = pairs
+                    .iter()
+                    .map(|(key, value)| format!("{}: {}", key.inspect(), value.inspect()))
+                    .collect();
+                format!("{{{}}}", pairs.join(", "))
+            }
+            Object::Null => "null".to_string(),
+            Object::ReturnValue(v) => v.inspect(),
+            Object::Break => "break".to_string(),
+            Object::Continue => "continue".to_string(),
+            Object::Error(message) => format!("ERROR: {}", message),
+        }
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Object::Integer(_) => "INTEGER",
+            Object::Float(_) => "FLOAT",
+            Object::Boolean(_) => "BOOLEAN",
+            Object::String(_) => "STRING",
+            Object::Array(_) => "ARRAY",
+            Object::Hash(_) => "HASH",
+            Object::Null => "NULL",
+            Object::ReturnValue(_) => "RETURN_VALUE",
+            Object::Break => "BREAK",
+            Object::Continue => "CONTINUE",
+            Object::Error(_) => "ERROR",
+        }
+    }
+
+    pub fn hash_key(&self) -> Option<HashKey> {
+        match self {
+            Object::Integer(v) => Some(HashKey::Integer(*v)),
+            Object::Boolean(v) => Some(HashKey::Boolean(*v)),
+            Object::String(v) => Some(HashKey::String(v.clone())),
+            _ => None,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        matches!(self, Object::Error(_))
+    }
+
+    /// Errors, `return` values and loop control end the evaluation of any
+    /// expression or statement they come out of, and are passed up unchanged.
+    pub fn stops_evaluation(&self) -> bool {
+        matches!(
+            self,
+            Object::Error(_) | Object::ReturnValue(_) | Object::Break | Object::Continue
+        )
+    }
+
+    pub fn is_truthy(&self) -> bool {
+        !matches!(self, Object::Null | Object::Boolean(false))
+    }
+}
+
+impl HashKey {
+    pub fn inspect(&self) -> String {
+        match self {
+            HashKey::Integer(v) => v.to_string(),
+            HashKey::Boolean(v) => v.to_string(),
+            HashKey::String(v) => v.clone(),
+        }
+    }
+
+    pub fn to_object(&self) -> Object {
+        match self {
+            HashKey::Integer(v) => Object::Integer(*v),
+            HashKey::Boolean(v) => Object::Boolean(*v),
+            HashKey::String(v) => Object::String(v.clone()),
+        }
+    }
+}
@@ -1,69 +1,183 @@
+use crate::token::TokenKind;
 use crate::{ast, lexer, token};
 use lazy_static::lazy_static;
 use std::collections::HashMap;
-
-const LOWEST: u8 = 1;
-const EQUALS: u8 = 2; // ==
-const LESSGREATER: u8 = 3; // > or <
-const SUM: u8 = 4; // +
-const PRODUCT: u8 = 5; // *
-const PREFIX: u8 = 6; // -X or !X
-const CALL: u8 = 7; // myFunction(x)
+use std::fmt;
+
+pub(crate) const LOWEST: u8 = 1;
+pub(crate) const ASSIGN: u8 = 2; // = or +=
+pub(crate) const LOGICAL_OR: u8 = 3; // ||
+pub(crate) const LOGICAL_AND: u8 = 4; // &&
+pub(crate) const EQUALS: u8 = 5; // ==
+pub(crate) const LESSGREATER: u8 = 6; // > or <
+pub(crate) const BIT_OR: u8 = 7; // |
+pub(crate) const BIT_XOR: u8 = 8; // ^
+pub(crate) const BIT_AND: u8 = 9; // &
+pub(crate) const SHIFT: u8 = 10; // << or >>
+pub(crate) const SUM: u8 = 11; // +
+pub(crate) const PRODUCT: u8 = 12; // *
+pub(crate) const PREFIX: u8 = 13; // -X or !X
+pub(crate) const EXPONENT: u8 = 14; // **
+pub(crate) const CALL: u8 = 15; // myFunction(x)
+pub(crate) const INDEX: u8 = 16; // array[index]
 
 type PrefixParseFn = fn(&mut Parser) -> Option<ast::Expressions>;
 type InfixParseFn = fn(ast::Expressions, &mut Parser) -> Option<ast::Expressions>;
 
 lazy_static! {
-    static ref PRECEDENTS: HashMap<token::Type, u8> = {
-        let mut m: HashMap<token::Type, u8> = HashMap::new();
-        m.insert(token::EQ.to_string(), EQUALS);
-        m.insert(token::NOT_EQ.to_string(), EQUALS);
-        m.insert(token::LT.to_string(), LESSGREATER);
-        m.insert(token::GT.to_string(), LESSGREATER);
-        m.insert(token::PLUS.to_string(), SUM);
-        m.insert(token::MINUS.to_string(), SUM);
-        m.insert(token::SLASH.to_string(), PRODUCT);
-        m.insert(token::ASTERISK.to_string(), PRODUCT);
+    static ref PRECEDENTS: HashMap<TokenKind, u8> = {
+        let mut m: HashMap<TokenKind, u8> = HashMap::new();
+        m.insert(TokenKind::Assign, ASSIGN);
+        m.insert(TokenKind::PlusAssign, ASSIGN);
+        m.insert(TokenKind::MinusAssign, ASSIGN);
+        m.insert(TokenKind::AsteriskAssign, ASSIGN);
+        m.insert(TokenKind::SlashAssign, ASSIGN);
+        m.insert(TokenKind::Or, LOGICAL_OR);
+        m.insert(TokenKind::And, LOGICAL_AND);
+        m.insert(TokenKind::Eq, EQUALS);
+        m.insert(TokenKind::NotEq, EQUALS);
+        m.insert(TokenKind::Lt, LESSGREATER);
+        m.insert(TokenKind::Gt, LESSGREATER);
+        m.insert(TokenKind::LtEq, LESSGREATER);
+        m.insert(TokenKind::GtEq, LESSGREATER);
+        m.insert(TokenKind::Pipe, BIT_OR);
+        m.insert(TokenKind::Caret, BIT_XOR);
+        m.insert(TokenKind::Ampersand, BIT_AND);
+        m.insert(TokenKind::ShiftLeft, SHIFT);
+        m.insert(TokenKind::ShiftRight, SHIFT);
+        m.insert(TokenKind::Plus, SUM);
+        m.insert(TokenKind::Minus, SUM);
+        m.insert(TokenKind::Slash, PRODUCT);
+        m.insert(TokenKind::Asterisk, PRODUCT);
+        m.insert(TokenKind::Percent, PRODUCT);
+        m.insert(TokenKind::Power, EXPONENT);
+        m.insert(TokenKind::LParen, CALL);
+        m.insert(TokenKind::LBracket, INDEX);
         m
     };
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseErrorKind {
+    UnexpectedToken,
+    NoPrefixParseFunction,
+    IllegalToken,
+    UnterminatedString,
+    UnterminatedComment,
+    InvalidNumberLiteral,
+    NumberOutOfRange,
+    OutsideLoop,
+    InvalidAssignmentTarget,
+}
+
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    pub expected: Option<TokenKind>,
+    pub found: token::Token,
+    pub span: token::Span,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}: ", self.span.line, self.span.column)?;
+
+        match self.kind {
+            ParseErrorKind::UnexpectedToken => write!(
+                f,
+                "expected {} but got {}",
+                self.expected.map_or("", TokenKind::as_str),
+                self.found.token_type
+            ),
+            ParseErrorKind::NoPrefixParseFunction => write!(
+                f,
+                "no prefix parse function found for {}",
+                self.found.token_type
+            ),
+            ParseErrorKind::IllegalToken => write!(f, "illegal token {}", self.found.literal),
+            ParseErrorKind::UnterminatedString => write!(f, "unterminated string"),
+            ParseErrorKind::UnterminatedComment => write!(f, "unterminated block comment"),
+            ParseErrorKind::InvalidNumberLiteral => {
+                write!(f, "invalid number literal {}", self.found.literal)
+            }
+            ParseErrorKind::NumberOutOfRange => {
+                write!(f, "number literal {} out of range", self.found.literal)
+            }
+            ParseErrorKind::OutsideLoop => write!(f, "{} outside of a loop", self.found.literal),
+            ParseErrorKind::InvalidAssignmentTarget => write!(
+                f,
+                "left-hand side of {} is not assignable",
+                self.found.literal
+            ),
+        }
+    }
+}
+
 pub struct Parser<'a> {
     lexer: &'a mut lexer::Lexer,
     current_token: token::Token,
     peek_token: token::Token,
-    errors: Vec<String>,
-    prefix_parse_functions: HashMap<token::Type, PrefixParseFn>,
-    infix_parse_functions: HashMap<token::Type, InfixParseFn>,
+    errors: Vec<ParseError>,
+    loop_depth: usize,
+    // `{` tokens before the current one that are still unmatched.
+    open_braces: usize,
+    prefix_parse_functions: HashMap<TokenKind, PrefixParseFn>,
+    infix_parse_functions: HashMap<TokenKind, InfixParseFn>,
 }
 
-pub fn new(lexer: &mut lexer::Lexer) -> Parser {
+pub fn new(lexer: &mut lexer::Lexer) -> Parser<'_> {
     let mut parser = Parser {
-        lexer: lexer,
-        current_token: token::new(token::NULL, "".to_string()),
-        peek_token: token::new(token::NULL, "".to_string()),
+        lexer,
+        current_token: token::new(TokenKind::Null, "".to_string()),
+        peek_token: token::new(TokenKind::Null, "".to_string()),
         errors: vec![],
+        loop_depth: 0,
+        open_braces: 0,
         prefix_parse_functions: HashMap::new(),
         infix_parse_functions: HashMap::new(),
     };
 
-    parser.register_prefix_fn(token::IDENT.to_string(), parse_identifier);
-    parser.register_prefix_fn(token::INT.to_string(), parse_integer_literal);
-    parser.register_prefix_fn(token::MINUS.to_string(), parse_prefix_expression);
-    parser.register_prefix_fn(token::BANG.to_string(), parse_prefix_expression);
-    parser.register_prefix_fn(token::TRUE.to_string(), parse_boolean_expression);
-    parser.register_prefix_fn(token::FALSE.to_string(), parse_boolean_expression);
-    parser.register_prefix_fn(token::LPAREN.to_string(), parse_grouped_expression);
-    parser.register_prefix_fn(token::IF.to_string(), parse_if_expression);
-
-    parser.register_infix_fn(token::PLUS.to_string(), parse_infix_expression);
-    parser.register_infix_fn(token::MINUS.to_string(), parse_infix_expression);
-    parser.register_infix_fn(token::SLASH.to_string(), parse_infix_expression);
-    parser.register_infix_fn(token::ASTERISK.to_string(), parse_infix_expression);
-    parser.register_infix_fn(token::EQ.to_string(), parse_infix_expression);
-    parser.register_infix_fn(token::NOT_EQ.to_string(), parse_infix_expression);
-    parser.register_infix_fn(token::LT.to_string(), parse_infix_expression);
-    parser.register_infix_fn(token::GT.to_string(), parse_infix_expression);
+    parser.register_prefix_fn(TokenKind::Ident, parse_identifier);
+    parser.register_prefix_fn(TokenKind::Int, parse_integer_literal);
+    parser.register_prefix_fn(TokenKind::Float, parse_float_literal);
+    parser.register_prefix_fn(TokenKind::String, parse_string_literal);
+    parser.register_prefix_fn(TokenKind::Minus, parse_prefix_expression);
+    parser.register_prefix_fn(TokenKind::Bang, parse_prefix_expression);
+    parser.register_prefix_fn(TokenKind::Tilde, parse_prefix_expression);
+    parser.register_prefix_fn(TokenKind::True, parse_boolean_expression);
+    parser.register_prefix_fn(TokenKind::False, parse_boolean_expression);
+    parser.register_prefix_fn(TokenKind::LParen, parse_grouped_expression);
+    parser.register_prefix_fn(TokenKind::If, parse_if_expression);
+    parser.register_prefix_fn(TokenKind::Function, parse_function_literal);
+    parser.register_prefix_fn(TokenKind::LBracket, parse_array_literal);
+    parser.register_prefix_fn(TokenKind::LBrace, parse_hash_literal);
+
+    parser.register_infix_fn(TokenKind::Plus, parse_infix_expression);
+    parser.register_infix_fn(TokenKind::Minus, parse_infix_expression);
+    parser.register_infix_fn(TokenKind::Slash, parse_infix_expression);
+    parser.register_infix_fn(TokenKind::Asterisk, parse_infix_expression);
+    parser.register_infix_fn(TokenKind::Percent, parse_infix_expression);
+    parser.register_infix_fn(TokenKind::Power, parse_infix_expression);
+    parser.register_infix_fn(TokenKind::Ampersand, parse_infix_expression);
+    parser.register_infix_fn(TokenKind::Pipe, parse_infix_expression);
+    parser.register_infix_fn(TokenKind::Caret, parse_infix_expression);
+    parser.register_infix_fn(TokenKind::ShiftLeft, parse_infix_expression);
+    parser.register_infix_fn(TokenKind::ShiftRight, parse_infix_expression);
+    parser.register_infix_fn(TokenKind::Eq, parse_infix_expression);
+    parser.register_infix_fn(TokenKind::NotEq, parse_infix_expression);
+    parser.register_infix_fn(TokenKind::Lt, parse_infix_expression);
+    parser.register_infix_fn(TokenKind::Gt, parse_infix_expression);
+    parser.register_infix_fn(TokenKind::LtEq, parse_infix_expression);
+    parser.register_infix_fn(TokenKind::GtEq, parse_infix_expression);
+    parser.register_infix_fn(TokenKind::And, parse_logical_expression);
+    parser.register_infix_fn(TokenKind::Or, parse_logical_expression);
+    parser.register_infix_fn(TokenKind::Assign, parse_assign_expression);
+    parser.register_infix_fn(TokenKind::PlusAssign, parse_assign_expression);
+    parser.register_infix_fn(TokenKind::MinusAssign, parse_assign_expression);
+    parser.register_infix_fn(TokenKind::AsteriskAssign, parse_assign_expression);
+    parser.register_infix_fn(TokenKind::SlashAssign, parse_assign_expression);
+    parser.register_infix_fn(TokenKind::LParen, parse_call_expression);
+    parser.register_infix_fn(TokenKind::LBracket, parse_index_expression);
 
     parser.next_token();
     parser.next_token();
@@ -74,7 +188,7 @@ pub fn new(lexer: &mut lexer::Lexer) -> Parser {
 fn parse_if_expression(parser: &mut Parser) -> Option<ast::Expressions> {
     let token = parser.current_token.clone();
 
-    if !parser.expect_peek_token(token::LPAREN) {
+    if !parser.expect_peek_token(TokenKind::LParen) {
         return None;
     }
 
@@ -88,32 +202,131 @@ fn parse_if_expression(parser: &mut Parser) -> Option<ast::Expressions> {
         return None;
     };
 
-    if !parser.expect_peek_token(token::RPAREN) {
+    if !parser.expect_peek_token(TokenKind::RParen) {
         return None;
     }
 
-    if !parser.expect_peek_token(token::LBRACE) {
+    if !parser.expect_peek_token(TokenKind::LBrace) {
         return None;
     }
 
     let consequence = parser.parse_block_statement();
     let mut alternative = None;
 
-    if parser.peek_token.token_type == token::ELSE {
+    if parser.peek_token.token_type == TokenKind::Else {
         parser.next_token();
 
-        if !parser.expect_peek_token(token::LBRACE) {
+        if !parser.expect_peek_token(TokenKind::LBrace) {
             return None;
         }
 
         alternative = Some(parser.parse_block_statement());
     }
 
-    Some(ast::Expressions::If(ast::IfStatement{
+    Some(ast::Expressions::If(ast::IfStatement {
         token,
         condition: Box::new(condition),
         consequence,
-        alternative
+        alternative,
+    }))
+}
+
+fn parse_function_literal(parser: &mut Parser) -> Option<ast::Expressions> {
+    let token = parser.current_token.clone();
+
+    if !parser.expect_peek_token(TokenKind::LParen) {
+        return None;
+    }
+
+    let parameters = parser.parse_function_parameters()?;
+
+    if !parser.expect_peek_token(TokenKind::LBrace) {
+        return None;
+    }
+
+    // `break` and `continue` cannot jump out of a function body.
+    let loop_depth = std::mem::replace(&mut parser.loop_depth, 0);
+    let body = parser.parse_block_statement();
+    parser.loop_depth = loop_depth;
+
+    Some(ast::Expressions::Function(ast::FunctionLiteral {
+        token,
+        parameters,
+        body,
+    }))
+}
+
+fn parse_call_expression(
+    function: ast::Expressions,
+    parser: &mut Parser,
+) -> Option<ast::Expressions> {
+    let token = parser.current_token.clone();
+    let arguments = parser.parse_expression_list(TokenKind::RParen)?;
+
+    Some(ast::Expressions::Call(ast::CallExpression {
+        token,
+        function: Box::new(function),
+        arguments,
+    }))
+}
+
+fn parse_array_literal(parser: &mut Parser) -> Option<ast::Expressions> {
+    let token = parser.current_token.clone();
+    let elements = parser.parse_expression_list(TokenKind::RBracket)?;
+
+    Some(ast::Expressions::Array(ast::ArrayLiteral {
+        token,
+        elements,
+    }))
+}
+
+// Block statements are only parsed where the grammar requires one (after `if`,
+// `else`, `fn(...)` and a `while` or `for` header), so a `{` reaching
+// expression position is a hash.
+fn parse_hash_literal(parser: &mut Parser) -> Option<ast::Expressions> {
+    let token = parser.current_token.clone();
+    let mut pairs = Vec::new();
+
+    while parser.peek_token.token_type != TokenKind::RBrace {
+        parser.next_token();
+        let key = parser.parse_expression(LOWEST)?;
+
+        if !parser.expect_peek_token(TokenKind::Colon) {
+            return None;
+        }
+
+        parser.next_token();
+        let value = parser.parse_expression(LOWEST)?;
+
+        pairs.push((key, value));
+
+        if parser.peek_token.token_type != TokenKind::RBrace
+            && !parser.expect_peek_token(TokenKind::Comma)
+        {
+            return None;
+        }
+    }
+
+    parser.next_token();
+
+    Some(ast::Expressions::Hash(ast::HashLiteral { token, pairs }))
+}
+
+fn parse_index_expression(left: ast::Expressions, parser: &mut Parser) -> Option<ast::Expressions> {
+    let token = parser.current_token.clone();
+
+    parser.next_token();
+
+    let index = parser.parse_expression(LOWEST)?;
+
+    if !parser.expect_peek_token(TokenKind::RBracket) {
+        return None;
+    }
+
+    Some(ast::Expressions::Index(ast::IndexExpression {
+        token,
+        left: Box::new(left),
+        index: Box::new(index),
     }))
 }
 
@@ -122,7 +335,7 @@ fn parse_grouped_expression(parser: &mut Parser) -> Option<ast::Expressions> {
 
     let expression = parser.parse_expression(LOWEST);
 
-    if !parser.expect_peek_token(token::RPAREN) {
+    if !parser.expect_peek_token(TokenKind::RParen) {
         return None;
     }
 
@@ -137,19 +350,62 @@ fn parse_identifier(parser: &mut Parser) -> Option<ast::Expressions> {
 }
 
 fn parse_integer_literal(parser: &mut Parser) -> Option<ast::Expressions> {
-    if let Ok(v) = parser.current_token.literal.parse::<i64>() {
-        return Some(ast::Expressions::IntegerLiteral(ast::IntegerLiteral {
+    let literal = parser.current_token.literal.replace('_', "");
+
+    let (digits, radix) = match literal.get(..2) {
+        Some("0x") | Some("0X") => (&literal[2..], 16),
+        Some("0o") | Some("0O") => (&literal[2..], 8),
+        Some("0b") | Some("0B") => (&literal[2..], 2),
+        _ => (literal.as_str(), 10),
+    };
+
+    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+        parser.current_token_error(ParseErrorKind::InvalidNumberLiteral);
+        return None;
+    }
+
+    match i64::from_str_radix(digits, radix) {
+        Ok(v) => Some(ast::Expressions::IntegerLiteral(ast::IntegerLiteral {
             token: parser.current_token.clone(),
             value: v,
-        }));
+        })),
+        Err(_) => {
+            parser.current_token_error(ParseErrorKind::NumberOutOfRange);
+            None
+        }
+    }
+}
+
+fn parse_float_literal(parser: &mut Parser) -> Option<ast::Expressions> {
+    let literal = parser.current_token.literal.replace('_', "");
+
+    match literal.parse::<f64>() {
+        Ok(v) if v.is_finite() => Some(ast::Expressions::FloatLiteral(ast::FloatLiteral {
+            token: parser.current_token.clone(),
+            value: v,
+        })),
+        Ok(_) => {
+            parser.current_token_error(ParseErrorKind::NumberOutOfRange);
+            None
+        }
+        Err(_) => {
+            parser.current_token_error(ParseErrorKind::InvalidNumberLiteral);
+            None
+        }
     }
-    None
+}
+
+fn parse_string_literal(parser: &mut Parser) -> Option<ast::Expressions> {
+    Some(ast::Expressions::StringLiteral(ast::StringLiteral {
+        token: parser.current_token.clone(),
+        value: parser.current_token.literal.clone(),
+    }))
 }
 
 fn parse_boolean_expression(parser: &mut Parser) -> Option<ast::Expressions> {
     Some(ast::Expressions::Boolean(ast::Boolean {
         token: parser.current_token.clone(),
-        value: parser.current_token.token_type == token::TRUE,
+        value: parser.current_token.token_type == TokenKind::True,
     }))
 }
 
@@ -174,7 +430,13 @@ fn parse_prefix_expression(parser: &mut Parser) -> Option<ast::Expressions> {
 fn parse_infix_expression(left: ast::Expressions, parser: &mut Parser) -> Option<ast::Expressions> {
     let current_token = parser.current_token.clone();
 
-    let precedence = parser.current_precedence();
+    let mut precedence = parser.current_precedence();
+
+    // Parsing the right operand one level lower lets an operator of the same
+    // precedence bind to the right: `a ** b ** c` is `a ** (b ** c)`.
+    if is_right_associative(current_token.token_type) {
+        precedence -= 1;
+    }
 
     parser.next_token();
 
@@ -190,33 +452,225 @@ fn parse_infix_expression(left: ast::Expressions, parser: &mut Parser) -> Option
     None
 }
 
+pub(crate) fn precedence(token_type: TokenKind) -> u8 {
+    PRECEDENTS.get(&token_type).cloned().unwrap_or(LOWEST)
+}
+
+pub(crate) fn is_right_associative(token_type: TokenKind) -> bool {
+    token_type == TokenKind::Power
+}
+
+fn parse_logical_expression(
+    left: ast::Expressions,
+    parser: &mut Parser,
+) -> Option<ast::Expressions> {
+    let current_token = parser.current_token.clone();
+
+    let precedence = parser.current_precedence();
+
+    parser.next_token();
+
+    let right = parser.parse_expression(precedence)?;
+
+    Some(ast::Expressions::Logical(ast::LogicalExpression {
+        token: current_token.clone(),
+        operator: current_token.literal,
+        left: Box::new(left),
+        right: Box::new(right),
+    }))
+}
+
+fn parse_assign_expression(
+    target: ast::Expressions,
+    parser: &mut Parser,
+) -> Option<ast::Expressions> {
+    let current_token = parser.current_token.clone();
+
+    parser.next_token();
+
+    // Assignment is right-associative: `a = b = c` is `a = (b = c)`.
+    let value = parser.parse_expression(ASSIGN - 1)?;
+
+    if !is_assignable(&target) {
+        parser.errors.push(ParseError {
+            kind: ParseErrorKind::InvalidAssignmentTarget,
+            expected: None,
+            span: current_token.span,
+            found: current_token,
+        });
+        return None;
+    }
+
+    Some(ast::Expressions::Assign(ast::AssignExpression {
+        token: current_token.clone(),
+        target: Box::new(target),
+        operator: current_token.literal,
+        value: Box::new(value),
+    }))
+}
+
+fn is_assignable(expression: &ast::Expressions) -> bool {
+    match expression {
+        ast::Expressions::Identifier(_) => true,
+        ast::Expressions::Index(i) => is_assignable(&i.left),
+        _ => false,
+    }
+}
+
 impl Parser<'_> {
     pub fn parse_program(&mut self) -> ast::Program {
         let mut program = ast::Program { statements: vec![] };
 
-        while self.current_token.token_type != token::EOF {
-            if let Some(statement) = self.parse_statement() {
-                program.statements.push(statement);
-            }
+        while self.current_token.token_type != TokenKind::Eof {
+            let start = self.current_token.span.start;
+            let open_braces = self.open_braces;
 
-            self.next_token()
+            match self.parse_statement() {
+                Some(statement) => {
+                    program.statements.push(statement);
+                    self.next_token();
+                }
+                None => self.synchronize(start, open_braces, false),
+            }
         }
 
         program
     }
 
+    pub fn errors(&self) -> &[ParseError] {
+        &self.errors
+    }
+
     fn parse_statement(&mut self) -> Option<ast::Statements> {
-        match self.current_token.token_type.as_str() {
-            token::LET => self.parse_let_statement(),
-            token::RETURN => self.parse_return_statement(),
+        match self.current_token.token_type {
+            TokenKind::Let => self.parse_let_statement(),
+            TokenKind::Return => self.parse_return_statement(),
+            TokenKind::While => self.parse_while_statement(),
+            TokenKind::For => self.parse_for_statement(),
+            TokenKind::Break | TokenKind::Continue => self.parse_loop_control_statement(),
             _ => self.parse_expression_statement(),
         }
     }
 
+    fn parse_while_statement(&mut self) -> Option<ast::Statements> {
+        let token = self.current_token.clone();
+
+        if !self.expect_peek_token(TokenKind::LParen) {
+            return None;
+        }
+
+        self.next_token();
+
+        let condition = self.parse_expression(LOWEST)?;
+
+        if !self.expect_peek_token(TokenKind::RParen) {
+            return None;
+        }
+
+        if !self.expect_peek_token(TokenKind::LBrace) {
+            return None;
+        }
+
+        self.loop_depth += 1;
+        let body = self.parse_block_statement();
+        self.loop_depth -= 1;
+
+        if self.peek_token.token_type == TokenKind::Semicolon {
+            self.next_token();
+        }
+
+        Some(ast::Statements::While(ast::WhileStatement {
+            token,
+            condition,
+            body,
+        }))
+    }
+
+    fn parse_for_statement(&mut self) -> Option<ast::Statements> {
+        let token = self.current_token.clone();
+
+        if !self.expect_peek_token(TokenKind::LParen) {
+            return None;
+        }
+
+        let mut variables = vec![];
+
+        loop {
+            if !self.expect_peek_token(TokenKind::Ident) {
+                return None;
+            }
+
+            variables.push(ast::Identifier {
+                token: self.current_token.clone(),
+                value: self.current_token.literal.clone(),
+            });
+
+            if variables.len() == 2 || self.peek_token.token_type != TokenKind::Comma {
+                break;
+            }
+            self.next_token();
+        }
+
+        if !self.expect_peek_token(TokenKind::In) {
+            return None;
+        }
+
+        self.next_token();
+
+        let iterable = self.parse_expression(LOWEST)?;
+
+        if !self.expect_peek_token(TokenKind::RParen) {
+            return None;
+        }
+
+        if !self.expect_peek_token(TokenKind::LBrace) {
+            return None;
+        }
+
+        self.loop_depth += 1;
+        let body = self.parse_block_statement();
+        self.loop_depth -= 1;
+
+        if self.peek_token.token_type == TokenKind::Semicolon {
+            self.next_token();
+        }
+
+        Some(ast::Statements::For(ast::ForStatement {
+            token,
+            variables,
+            iterable,
+            body,
+        }))
+    }
+
+    fn parse_loop_control_statement(&mut self) -> Option<ast::Statements> {
+        let token = self.current_token.clone();
+
+        if self.peek_token.token_type == TokenKind::Semicolon {
+            self.next_token();
+        }
+
+        if self.loop_depth == 0 {
+            self.errors.push(ParseError {
+                kind: ParseErrorKind::OutsideLoop,
+                expected: None,
+                span: token.span,
+                found: token,
+            });
+            return None;
+        }
+
+        if token.token_type == TokenKind::Break {
+            Some(ast::Statements::Break(ast::BreakStatement { token }))
+        } else {
+            Some(ast::Statements::Continue(ast::ContinueStatement { token }))
+        }
+    }
+
     fn parse_let_statement(&mut self) -> Option<ast::Statements> {
         let current_token = self.current_token.clone();
 
-        if !self.expect_peek_token(token::IDENT) {
+        if !self.expect_peek_token(TokenKind::Ident) {
             return None;
         }
 
@@ -225,36 +679,44 @@ impl Parser<'_> {
             value: self.current_token.literal.clone(),
         };
 
-        if !self.expect_peek_token(token::ASSIGN) {
+        if !self.expect_peek_token(TokenKind::Assign) {
             return None;
         }
 
-        while self.current_token.token_type == token::SEMICOLON {
+        self.next_token();
+
+        let value = self.parse_expression(LOWEST)?;
+
+        if self.peek_token.token_type == TokenKind::Semicolon {
             self.next_token();
         }
 
         Some(ast::Statements::Let(ast::LetStatement {
             token: current_token,
             name: identifier,
-            value: ast::Expressions::Identifier(ast::Identifier {
-                token: token::new(token::IDENT, "".to_string()),
-                value: "".to_string(),
-            }),
+            value,
         }))
     }
 
     fn parse_return_statement(&mut self) -> Option<ast::Statements> {
-        let statement = ast::ReturnStatement {
-            token: self.current_token.clone(),
-        };
+        let current_token = self.current_token.clone();
 
-        self.next_token();
+        let return_value = match self.peek_token.token_type {
+            TokenKind::Semicolon | TokenKind::RBrace | TokenKind::Eof => None,
+            _ => {
+                self.next_token();
+                Some(self.parse_expression(LOWEST)?)
+            }
+        };
 
-        while self.current_token.token_type == token::SEMICOLON {
+        if self.peek_token.token_type == TokenKind::Semicolon {
             self.next_token();
         }
 
-        Some(ast::Statements::Return(statement))
+        Some(ast::Statements::Return(ast::ReturnStatement {
+            token: current_token,
+            return_value,
+        }))
     }
 
     fn parse_expression_statement(&mut self) -> Option<ast::Statements> {
@@ -262,7 +724,7 @@ impl Parser<'_> {
 
         let expression = self.parse_expression(LOWEST);
 
-        if self.peek_token.token_type == token::SEMICOLON {
+        if self.peek_token.token_type == TokenKind::Semicolon {
             self.next_token();
         }
 
@@ -277,93 +739,229 @@ impl Parser<'_> {
     }
 
     fn parse_expression(&mut self, precedence: u8) -> Option<ast::Expressions> {
-        let token_type = &self.current_token.token_type;
-        if let Some(prefix) = self.prefix_parse_functions.get(token_type) {
-            let mut left_expresion = prefix(self);
-
-            while self.peek_token.token_type != token::SEMICOLON
-                && precedence < self.peek_precedence()
-            {
-                if let Some(&inflix) = self.infix_parse_functions.get(&self.peek_token.token_type) {
+        let prefix = match self
+            .prefix_parse_functions
+            .get(&self.current_token.token_type)
+        {
+            Some(&prefix) => prefix,
+            None => {
+                let kind = match self.current_token.token_type {
+                    TokenKind::Illegal => ParseErrorKind::IllegalToken,
+                    TokenKind::UnterminatedString => ParseErrorKind::UnterminatedString,
+                    TokenKind::UnterminatedComment => ParseErrorKind::UnterminatedComment,
+                    _ => ParseErrorKind::NoPrefixParseFunction,
+                };
+
+                self.current_token_error(kind);
+                return None;
+            }
+        };
+
+        let mut left_expression = prefix(self)?;
+
+        while self.peek_token.token_type != TokenKind::Semicolon
+            && precedence < self.peek_precedence()
+        {
+            // A token with a precedence but no infix parser cannot continue the
+            // expression, so leave it for the caller rather than spin on it.
+            let infix = match self.infix_parse_functions.get(&self.peek_token.token_type) {
+                Some(&infix) => infix,
+                None => break,
+            };
+
+            self.next_token();
+
+            left_expression = infix(left_expression, self)?;
+        }
+
+        Some(left_expression)
+    }
+
+    // Skips the rest of a statement that failed to parse so that one mistake
+    // produces one error. Stops on the first token of the next statement: after
+    // a `;`, on a statement keyword, or on the `}` closing the enclosing block.
+    // Braces opened since the statement began, `open_braces` being the count
+    // at its start, are skipped as a whole, and a stray `}` at the top level is
+    // dropped.
+    fn synchronize(&mut self, start: usize, open_braces: usize, in_block: bool) {
+        loop {
+            let depth = self.open_braces - open_braces;
+
+            match self.current_token.token_type {
+                TokenKind::Eof => return,
+                TokenKind::Semicolon if depth == 0 => {
                     self.next_token();
+                    return;
+                }
+                TokenKind::RBrace if depth == 0 => {
+                    if !in_block {
+                        self.next_token();
+                    }
+                    return;
+                }
+                TokenKind::Let
+                | TokenKind::Return
+                | TokenKind::While
+                | TokenKind::For
+                | TokenKind::Break
+                | TokenKind::Continue
+                    if depth == 0 && self.current_token.span.start != start =>
+                {
+                    return
+                }
+                _ => {}
+            }
+
+            self.next_token();
+        }
+    }
+
+    fn parse_block_statement(&mut self) -> ast::BlockStatement {
+        let token = self.current_token.clone();
+        let mut statements = Vec::new();
+
+        self.next_token();
+
+        while self.current_token.token_type != TokenKind::RBrace
+            && self.current_token.token_type != TokenKind::Eof
+        {
+            let start = self.current_token.span.start;
+            let open_braces = self.open_braces;
 
-                    left_expresion = inflix(left_expresion.unwrap(), self);
+            match self.parse_statement() {
+                Some(statement) => {
+                    statements.push(statement);
+                    self.next_token();
                 }
+                None => self.synchronize(start, open_braces, true),
             }
-            return left_expresion;
         }
 
-        let error = format!(
-            "Parser error: No prefix parse function found for {}",
-            self.current_token.token_type
+        // Nested blocks all run out at the same end of input; report it once.
+        let reported_eof = matches!(
+            self.errors.last(),
+            Some(e) if e.found.token_type == TokenKind::Eof
         );
-        println!("{}", error);
-        self.errors.push(error);
-        None
+        if self.current_token.token_type == TokenKind::Eof && !reported_eof {
+            self.errors.push(ParseError {
+                kind: ParseErrorKind::UnexpectedToken,
+                expected: Some(TokenKind::RBrace),
+                found: self.current_token.clone(),
+                span: self.current_token.span,
+            });
+        }
+
+        ast::BlockStatement { token, statements }
     }
 
-    fn parse_identifier(&self) -> ast::Expressions {
-        ast::Expressions::Identifier(ast::Identifier {
+    fn parse_function_parameters(&mut self) -> Option<Vec<ast::Identifier>> {
+        let mut identifiers = Vec::new();
+
+        if self.peek_token.token_type == TokenKind::RParen {
+            self.next_token();
+            return Some(identifiers);
+        }
+
+        if !self.expect_peek_token(TokenKind::Ident) {
+            return None;
+        }
+
+        identifiers.push(ast::Identifier {
             token: self.current_token.clone(),
             value: self.current_token.literal.clone(),
-        })
-    }
+        });
 
-    fn parse_block_statement(&mut self) -> ast::BlockStatement {
-        let token = self.current_token.clone();
-        let mut statements = Vec::new();
+        while self.peek_token.token_type == TokenKind::Comma {
+            self.next_token();
 
-        while self.current_token.token_type != token::RBRACE && self.current_token.token_type != token::EOF {
-            if let Some(statement) = self.parse_statement() {
-                statements.push(statement);
+            if !self.expect_peek_token(TokenKind::Ident) {
+                return None;
             }
 
-            self.next_token()
+            identifiers.push(ast::Identifier {
+                token: self.current_token.clone(),
+                value: self.current_token.literal.clone(),
+            });
         }
 
-        ast::BlockStatement{token, statements}
+        if !self.expect_peek_token(TokenKind::RParen) {
+            return None;
+        }
+
+        Some(identifiers)
     }
 
-    fn register_prefix_fn(&mut self, token_type: token::Type, function: PrefixParseFn) {
+    fn parse_expression_list(&mut self, end: TokenKind) -> Option<Vec<ast::Expressions>> {
+        let mut list = Vec::new();
+
+        if self.peek_token.token_type == end {
+            self.next_token();
+            return Some(list);
+        }
+
+        self.next_token();
+        list.push(self.parse_expression(LOWEST)?);
+
+        while self.peek_token.token_type == TokenKind::Comma {
+            self.next_token();
+            self.next_token();
+            list.push(self.parse_expression(LOWEST)?);
+        }
+
+        if !self.expect_peek_token(end) {
+            return None;
+        }
+
+        Some(list)
+    }
+
+    fn register_prefix_fn(&mut self, token_type: TokenKind, function: PrefixParseFn) {
         self.prefix_parse_functions.insert(token_type, function);
     }
 
-    fn register_infix_fn(&mut self, token_type: token::Type, function: InfixParseFn) {
+    fn register_infix_fn(&mut self, token_type: TokenKind, function: InfixParseFn) {
         self.infix_parse_functions.insert(token_type, function);
     }
 
     fn next_token(&mut self) {
+        match self.current_token.token_type {
+            TokenKind::LBrace => self.open_braces += 1,
+            TokenKind::RBrace => self.open_braces = self.open_braces.saturating_sub(1),
+            _ => {}
+        }
         self.current_token = self.peek_token.clone();
-        self.peek_token = self.lexer.next_token().clone();
+        self.peek_token = self.lexer.next_token();
     }
 
     fn peek_precedence(&self) -> u8 {
-        if let Some(&precendence) = PRECEDENTS.get(&self.peek_token.token_type) {
-            return precendence;
-        }
-        LOWEST
+        precedence(self.peek_token.token_type)
     }
 
     fn current_precedence(&self) -> u8 {
-        if let Some(&precendence) = PRECEDENTS.get(&self.current_token.token_type) {
-            return precendence;
-        }
-        LOWEST
+        precedence(self.current_token.token_type)
     }
 
-    fn expect_peek_token(&mut self, expected: &str) -> bool {
-        if self.peek_token.token_type == expected.to_string() {
+    fn current_token_error(&mut self, kind: ParseErrorKind) {
+        self.errors.push(ParseError {
+            kind,
+            expected: None,
+            found: self.current_token.clone(),
+            span: self.current_token.span,
+        });
+    }
+
+    fn expect_peek_token(&mut self, expected: TokenKind) -> bool {
+        if self.peek_token.token_type == expected {
             self.next_token();
             return true;
         }
 
-        let error = format!(
-            "Parser Error: Expected {} but got {}",
-            expected, self.peek_token.token_type
-        );
-
-        println!("{}", &error);
-        self.errors.push(error);
+        self.errors.push(ParseError {
+            kind: ParseErrorKind::UnexpectedToken,
+            expected: Some(expected),
+            found: self.peek_token.clone(),
+            span: self.peek_token.span,
+        });
 
         false
     }
@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod tests {
     use crate::parser::*;
+    use crate::token::TokenKind;
     use crate::{ast, lexer};
 
     #[test]
@@ -15,30 +16,50 @@ mod tests {
 
         let program = parser.parse_program();
 
-        assert_eq!(program.statements.len(), 6);
+        assert_eq!(program.statements.len(), 3);
 
-        let expected = [
-            ("let", "x"),
-            ("5", "5"),
-            ("let", "y"),
-            ("10", "10"),
-            ("let", "foobar"),
-            ("838383", "838383"),
-        ];
+        let expected = [("x", 5), ("y", 10), ("foobar", 838383)];
 
         for (index, &val) in expected.iter().enumerate() {
             let statement = &program.statements[index];
 
-            assert_eq!(statement.token_literal(), val.0);
+            assert_eq!(statement.token_literal(), "let");
 
-            match statement {
-                ast::Statements::Let(s) => {
-                    assert_eq!(s.name.value, val.1);
-                    assert_eq!(s.name.token_literal(), val.1);
-                }
-                ast::Statements::Expression(e) => assert_eq!(e.token.literal, val.1),
-                _ => unreachable!(),
-            }
+            let let_statement = statement.let_statement();
+            assert_eq!(let_statement.name.value, val.0);
+            assert_eq!(let_statement.name.token_literal(), val.0);
+            assert_eq!(let_statement.value.integer_literal().value, val.1);
+        }
+    }
+
+    #[test]
+    fn test_let_statement_with_expressions() {
+        let tests = [
+            ("let x = 5 * y;", "x", "let x = (5 * y);"),
+            ("let y = true;", "y", "let y = true;"),
+            ("let foobar = y", "foobar", "let foobar = y;"),
+            (
+                "let a = -(b + c) * d / e;",
+                "a",
+                "let a = (((-(b + c)) * d) / e);",
+            ),
+            (
+                "let b = 1 + 2 == 3 != false;",
+                "b",
+                "let b = (((1 + 2) == 3) != false);",
+            ),
+        ];
+
+        for &test in tests.iter() {
+            let mut lexer = lexer::new(test.0.to_string());
+            let mut parser = new(&mut lexer);
+
+            let program = parser.parse_program();
+
+            assert!(parser.errors().is_empty());
+            assert_eq!(program.statements.len(), 1);
+            assert_eq!(program.statements[0].let_statement().name.value, test.1);
+            assert_eq!(program.to_string(), test.2);
         }
     }
 
@@ -57,11 +78,59 @@ mod tests {
 
         assert_eq!(program.statements.len(), 3);
 
+        let expected = [5, 10, 993322];
+
+        for (index, &val) in expected.iter().enumerate() {
+            let statement = program.statements[index].return_statement();
+
+            assert_eq!(statement.token.literal, "return");
+            assert_eq!(
+                statement
+                    .return_value
+                    .as_ref()
+                    .unwrap()
+                    .integer_literal()
+                    .value,
+                val
+            );
+        }
+    }
+
+    #[test]
+    fn test_return_statement_to_string() {
+        let tests = [
+            ("return x * y;", "return (x * y);"),
+            ("return -a", "return (-a);"),
+            ("return;", "return;"),
+            ("return", "return;"),
+            ("if (x) { return; }", "if (x) { return; }"),
+        ];
+
+        for &test in tests.iter() {
+            let mut lexer = lexer::new(test.0.to_string());
+            let mut parser = new(&mut lexer);
+
+            let program = parser.parse_program();
+
+            assert!(parser.errors().is_empty());
+            assert_eq!(program.statements.len(), 1);
+            assert_eq!(program.to_string(), test.1);
+        }
+    }
+
+    #[test]
+    fn test_bare_return_statement() {
+        let input = "return; return";
+
+        let mut lexer = lexer::new(input.to_string());
+        let mut parser = new(&mut lexer);
+
+        let program = parser.parse_program();
+
+        assert_eq!(program.statements.len(), 2);
+
         for statement in program.statements.iter() {
-            match statement {
-                ast::Statements::Return(_) => {}
-                _ => unreachable!(),
-            }
+            assert!(statement.return_statement().return_value.is_none());
         }
     }
 
@@ -103,7 +172,7 @@ mod tests {
 
     #[test]
     fn test_prefix_operator() {
-        let tests = [("!5;", "!", 5), ("-15;", "-", 15)];
+        let tests = [("!5;", "!", 5), ("-15;", "-", 15), ("~7;", "~", 7)];
 
         for &test in tests.iter() {
             let input = test.0;
@@ -155,6 +224,15 @@ mod tests {
             ("5 < 5;", 5, "<", 5),
             ("5 == 5;", 5, "==", 5),
             ("5 != 5;", 5, "!=", 5),
+            ("5 <= 5;", 5, "<=", 5),
+            ("5 >= 5;", 5, ">=", 5),
+            ("5 % 5;", 5, "%", 5),
+            ("5 ** 5;", 5, "**", 5),
+            ("5 & 5;", 5, "&", 5),
+            ("5 | 5;", 5, "|", 5),
+            ("5 ^ 5;", 5, "^", 5),
+            ("5 << 5;", 5, "<<", 5),
+            ("5 >> 5;", 5, ">>", 5),
         ];
 
         for &test in tests.iter() {
@@ -213,7 +291,7 @@ mod tests {
             ("a * b * c", "((a * b) * c)"),
             ("a * b / c", "((a * b) / c)"),
             ("a + b * c + d / e - f", "(((a + (b * c)) + (d / e)) - f)"),
-            ("3 + 4; -5 * 5", "(3 + 4)((-5) * 5)"),
+            ("3 + 4; -5 * 5", "(3 + 4); ((-5) * 5)"),
             ("5 > 4 == 3 < 4", "((5 > 4) == (3 < 4))"),
             ("5 < 4 != 3 > 4", "((5 < 4) != (3 > 4))"),
             (
@@ -233,6 +311,64 @@ mod tests {
             ("2 / (5 + 5)", "(2 / (5 + 5))"),
             ("-(5 + 5)", "(-(5 + 5))"),
             ("!(true == true)", "(!(true == true))"),
+            ("a + add(b * c) + d", "((a + add((b * c))) + d)"),
+            (
+                "add(a, b, 1, 2 * 3, 4 + 5, add(6, 7 * 8))",
+                "add(a, b, 1, (2 * 3), (4 + 5), add(6, (7 * 8)))",
+            ),
+            (
+                "add(a + b + c * d / f + g)",
+                "add((((a + b) + ((c * d) / f)) + g))",
+            ),
+            ("-add(x)(y)", "(-add(x)(y))"),
+            (
+                "a * [1, 2, 3, 4][b * c] * d",
+                "((a * ([1, 2, 3, 4][(b * c)])) * d)",
+            ),
+            (
+                "add(a * b[2], b[1], 2 * [1, 2][1])",
+                "add((a * (b[2])), (b[1]), (2 * ([1, 2][1])))",
+            ),
+            ("a[0][1][2]", "(((a[0])[1])[2])"),
+            ("a <= b == c >= d", "((a <= b) == (c >= d))"),
+            ("a && b || c && d", "((a && b) || (c && d))"),
+            ("a || b && c", "(a || (b && c))"),
+            ("a || b || c", "((a || b) || c)"),
+            ("a % b * c", "((a % b) * c)"),
+            ("a + b % c", "(a + (b % c))"),
+            ("a ** b ** c", "(a ** (b ** c))"),
+            ("a * b ** c", "(a * (b ** c))"),
+            ("a ** b * c", "((a ** b) * c)"),
+            ("-a ** b", "(-(a ** b))"),
+            ("a ** -b", "(a ** (-b))"),
+            ("~a ** b", "(~(a ** b))"),
+            ("f(a) ** b[0]", "(f(a) ** (b[0]))"),
+            ("a << b + c", "(a << (b + c))"),
+            ("a & b << c", "(a & (b << c))"),
+            ("a | b ^ c & d", "(a | (b ^ (c & d)))"),
+            ("a ^ b | c", "((a ^ b) | c)"),
+            ("a & b == c", "((a & b) == c)"),
+            ("a | b < c", "((a | b) < c)"),
+            ("a >> b >> c", "((a >> b) >> c)"),
+            ("~a & ~b", "((~a) & (~b))"),
+            ("1 + 2.5 * 3", "(1 + (2.5 * 3))"),
+            ("1.5 * 2 + 3", "((1.5 * 2) + 3)"),
+            ("-0.5 ** 2", "(-(0.5 ** 2))"),
+            ("2 ** 0.5 ** 2", "(2 ** (0.5 ** 2))"),
+            ("1e3 / 2.0 - 1", "((1000.0 / 2.0) - 1)"),
+            ("3.0 < 4 == 2.5 >= 1e-9", "((3.0 < 4) == (2.5 >= 1e-9))"),
+            ("a == b && c != d", "((a == b) && (c != d))"),
+            (
+                "x >= 0 && x < 10 || !done",
+                "(((x >= 0) && (x < 10)) || (!done))",
+            ),
+            ("f(x)[0]", "(f(x)[0])"),
+            ("-a[0]", "(-(a[0]))"),
+            ("x = 5", "(x = 5)"),
+            ("x = y = 5", "(x = (y = 5))"),
+            ("x += 1 * 2", "(x += (1 * 2))"),
+            ("a[0] -= b || c", "((a[0]) -= (b || c))"),
+            ("m[\"k\"][1] /= 2", "(((m[\"k\"])[1]) /= 2)"),
         ];
 
         for &test in tests.iter() {
@@ -260,14 +396,14 @@ mod tests {
 
         let program = parser.parse_program();
 
-        assert_eq!(program.statements.len(), 6);
+        assert_eq!(program.statements.len(), 4);
 
-        assert_eq!(program.statements[0].expression().boolean().value, true);
-        assert_eq!(program.statements[1].expression().boolean().value, false);
+        assert!(program.statements[0].expression().boolean().value);
+        assert!(!program.statements[1].expression().boolean().value);
         assert_eq!(program.statements[2].let_statement().name.value, "foobar");
-        assert_eq!(program.statements[3].expression().boolean().value, true);
-        assert_eq!(program.statements[4].let_statement().name.value, "barfoo");
-        assert_eq!(program.statements[5].expression().boolean().value, false);
+        assert!(program.statements[2].let_statement().value.boolean().value);
+        assert_eq!(program.statements[3].let_statement().name.value, "barfoo");
+        assert!(!program.statements[3].let_statement().value.boolean().value);
     }
 
     #[test]
@@ -286,7 +422,7 @@ mod tests {
         assert_eq!(statement.condition.infix().operator, "<");
         assert_eq!(statement.condition.infix().right.identifier().value, "y");
 
-        assert_eq!(statement.alternative.is_none(), true);
+        assert!(statement.alternative.is_none());
 
         let consequence = &statement.consequence;
         assert_eq!(consequence.statements.len(), 1);
@@ -311,7 +447,7 @@ mod tests {
         assert_eq!(statement.condition.infix().operator, "<");
         assert_eq!(statement.condition.infix().right.identifier().value, "y");
 
-        assert_eq!(statement.alternative.is_some(), true);
+        assert!(statement.alternative.is_some());
 
         let alternative = statement.alternative.as_ref().unwrap();
         assert_eq!(alternative.statements.len(), 1);
@@ -320,6 +456,925 @@ mod tests {
         let consequence = &statement.consequence;
         assert_eq!(consequence.statements.len(), 1);
         assert_eq!(consequence.statements[0].token_literal(), "x");
+    }
+
+    #[test]
+    fn test_function_literal_parsing() {
+        let input = "fn(x, y) { x + y; }";
+
+        let mut lexer = lexer::new(input.to_string());
+        let mut parser = new(&mut lexer);
+
+        let program = parser.parse_program();
+
+        assert!(parser.errors().is_empty());
+        assert_eq!(program.statements.len(), 1);
+
+        let function = program.statements[0].expression().function();
+        assert_eq!(function.parameters.len(), 2);
+        assert_eq!(function.parameters[0].value, "x");
+        assert_eq!(function.parameters[1].value, "y");
+
+        assert_eq!(function.body.statements.len(), 1);
+        let body = function.body.statements[0].expression().infix();
+        assert_eq!(body.left.identifier().value, "x");
+        assert_eq!(body.operator, "+");
+        assert_eq!(body.right.identifier().value, "y");
+    }
+
+    #[test]
+    fn test_function_parameter_parsing() {
+        let tests: [(&str, &[&str]); 3] = [
+            ("fn() {};", &[]),
+            ("fn(x) {};", &["x"]),
+            ("fn(x, y, z) {};", &["x", "y", "z"]),
+        ];
+
+        for test in tests.iter() {
+            let mut lexer = lexer::new(test.0.to_string());
+            let mut parser = new(&mut lexer);
+
+            let program = parser.parse_program();
+
+            assert!(parser.errors().is_empty());
+
+            let function = program.statements[0].expression().function();
+            let parameters: Vec<&str> = function
+                .parameters
+                .iter()
+                .map(|p| p.value.as_str())
+                .collect();
+
+            assert_eq!(parameters, test.1);
+        }
+    }
+
+    #[test]
+    fn test_call_expression_parsing() {
+        let input = "add(1, 2 * 3, 4 + 5);";
+
+        let mut lexer = lexer::new(input.to_string());
+        let mut parser = new(&mut lexer);
+
+        let program = parser.parse_program();
+
+        assert!(parser.errors().is_empty());
+        assert_eq!(program.statements.len(), 1);
+
+        let call = program.statements[0].expression().call();
+        assert_eq!(call.function.identifier().value, "add");
+        assert_eq!(call.arguments.len(), 3);
+        assert_eq!(call.arguments[0].integer_literal().value, 1);
+        assert_eq!(call.arguments[1].to_string(), "(2 * 3)");
+        assert_eq!(call.arguments[2].to_string(), "(4 + 5)");
+    }
+
+    #[test]
+    fn test_function_to_string() {
+        let tests = [
+            ("fn(x, y) { x + y; }", "fn(x, y) { (x + y) }"),
+            (
+                "let add = fn(a) { return a; };",
+                "let add = fn(a) { return a; };",
+            ),
+            ("fn() { 1 }()", "fn() { 1 }()"),
+        ];
+
+        for &test in tests.iter() {
+            let mut lexer = lexer::new(test.0.to_string());
+            let mut parser = new(&mut lexer);
+
+            let program = parser.parse_program();
+
+            assert!(parser.errors().is_empty());
+            assert_eq!(program.to_string(), test.1);
+        }
+    }
+
+    #[test]
+    fn test_parser_errors() {
+        let input = "let x 5;\n\n  let y = ;";
+
+        let mut lexer = lexer::new(input.to_string());
+        let mut parser = new(&mut lexer);
+
+        parser.parse_program();
+
+        let errors = parser.errors();
+        let expected = [
+            (
+                ParseErrorKind::UnexpectedToken,
+                Some(TokenKind::Assign),
+                TokenKind::Int,
+                1,
+                7,
+            ),
+            (
+                ParseErrorKind::NoPrefixParseFunction,
+                None,
+                TokenKind::Semicolon,
+                3,
+                11,
+            ),
+        ];
+
+        assert_eq!(errors.len(), expected.len());
+
+        for (error, test) in errors.iter().zip(expected.iter()) {
+            assert_eq!(error.kind, test.0);
+            assert_eq!(error.expected, test.1);
+            assert_eq!(error.found.token_type, test.2);
+            assert_eq!(error.span.line, test.3);
+            assert_eq!(error.span.column, test.4);
+        }
+    }
+
+    #[test]
+    fn test_error_recovery() {
+        let input = "let x = 5;
+let = 10;
+let y 7;
+let z = x * ;
+if (x { let w = 1; }
+let ok = x;";
+
+        let mut lexer = lexer::new(input.to_string());
+        let mut parser = new(&mut lexer);
+
+        let program = parser.parse_program();
+
+        let errors: Vec<String> = parser.errors().iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            errors,
+            [
+                "line 2, column 5: expected IDENT but got =",
+                "line 3, column 7: expected = but got INT",
+                "line 4, column 13: no prefix parse function found for ;",
+                "line 5, column 7: expected ) but got {",
+            ]
+        );
+        assert_eq!(program.to_string(), "let x = 5; let ok = x;");
+    }
+
+    #[test]
+    fn test_error_recovery_in_blocks() {
+        let tests = [
+            ("if (x) { let = 1; y } z", "if (x) { y }; z", 1),
+            ("while (x) { 1 + } 2", "while (x) {} 2", 1),
+            ("fn() { let a 1; let b = 2; }", "fn() { let b = 2; }", 1),
+            ("let a = 1; } let b = 2;", "let a = 1; let b = 2;", 1),
+            ("let a = 1 + } let b = 2;", "let b = 2;", 1),
+            ("5 + * 2; 3", "3", 1),
+            ("let a = ; let b = ; c", "c", 2),
+            ("let h = {1: }; let y = 2;", "let y = 2;", 1),
+            (
+                "let f = fn() { let h = {1: }; let y = 2; }; let z = 3;",
+                "let f = fn() { let y = 2; }; let z = 3;",
+                1,
+            ),
+        ];
+
+        for &test in tests.iter() {
+            let mut lexer = lexer::new(test.0.to_string());
+            let mut parser = new(&mut lexer);
+
+            let program = parser.parse_program();
+
+            assert_eq!(program.to_string(), test.1, "{}", test.0);
+            assert_eq!(parser.errors().len(), test.2, "{}", test.0);
+        }
+    }
+
+    fn parse_with_timeout(inputs: Vec<String>) -> Vec<(String, usize)> {
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        std::thread::spawn(move || {
+            let results: Vec<(String, usize)> = inputs
+                .into_iter()
+                .map(|input| {
+                    let mut lexer = lexer::new(input.clone());
+                    let mut parser = new(&mut lexer);
+                    parser.parse_program();
+                    let errors = parser.errors().len();
+                    (input, errors)
+                })
+                .collect();
+            sender.send(results).unwrap();
+        });
+
+        receiver
+            .recv_timeout(std::time::Duration::from_secs(30))
+            .expect("parser hung or panicked")
+    }
+
+    #[test]
+    fn test_unclosed_block() {
+        let input = "fn(x) {\n  if (x) { x";
+
+        let mut lexer = lexer::new(input.to_string());
+        let mut parser = new(&mut lexer);
+
+        parser.parse_program();
+
+        let errors = parser.errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].to_string(),
+            "line 2, column 13: expected } but got EOF"
+        );
+    }
+
+    #[test]
+    fn test_malformed_programs_terminate() {
+        let corpus = [
+            "(1 + ) * 2",
+            "(1 + ) [0]",
+            "-",
+            "!(",
+            "1 +",
+            "a[",
+            "a[1",
+            "a[1 +] + 2",
+            "f(",
+            "f(1,",
+            "f(1 2) * 3",
+            "fn(",
+            "fn(x,",
+            "fn(x) {",
+            "fn x { x }",
+            "if",
+            "if (",
+            "if (x",
+            "if (x) {",
+            "if (x) { 1 } else",
+            "if (x) { 1 } else {",
+            "{",
+            "{1",
+            "{1:",
+            "{1: 2,,}",
+            "[1, , 2]",
+            "[1 2]",
+            "let",
+            "let x",
+            "let x =",
+            "return (",
+            "while (",
+            "while (x) {",
+            "for (",
+            "for (x in",
+            "for (x, in y) {}",
+            "x = = 1",
+            "1 = 2 + 3 * 4",
+            "x += ) - 1",
+            "&& x",
+            "x ||",
+            ")))",
+            "]]]",
+            "}}}",
+            ",,,",
+            "::",
+            "\"unterminated",
+            "/* unterminated",
+            "0x",
+            "99999999999999999999 + 1",
+            "@ # $",
+        ];
+        let inputs: Vec<String> = corpus.iter().map(|input| input.to_string()).collect();
+
+        for (input, errors) in parse_with_timeout(inputs) {
+            assert!(errors > 0, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_random_token_soup_terminates() {
+        let fragments = [
+            "let", "x", "=", "+=", "1", "2.5", "\"s\"", "+", "-", "*", "**", "/", "!", "~", "&&",
+            "||", "==", "<", ">=", "<<", "(", ")", "[", "]", "{", "}", ",", ":", ";", "fn", "if",
+            "else", "return", "while", "for", "in", "break", "continue", "true", "@",
+        ];
+
+        // A fixed linear congruential generator keeps the corpus reproducible.
+        let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = move || {
+            seed = seed
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            (seed >> 33) as usize
+        };
+
+        let inputs: Vec<String> = (0..2000)
+            .map(|_| {
+                let length = next() % 24;
+                (0..length)
+                    .map(|_| fragments[next() % fragments.len()])
+                    .collect::<Vec<&str>>()
+                    .join(" ")
+            })
+            .collect();
+
+        assert_eq!(parse_with_timeout(inputs).len(), 2000);
+    }
+
+    #[test]
+    fn test_parser_error_display() {
+        let input = "fn(x y) {}";
+
+        let mut lexer = lexer::new(input.to_string());
+        let mut parser = new(&mut lexer);
+
+        parser.parse_program();
+
+        assert_eq!(
+            parser.errors()[0].to_string(),
+            "line 1, column 6: expected ) but got IDENT"
+        );
+    }
+
+    #[test]
+    fn test_string_literal_expression() {
+        let input = r#""hello world";"#;
+
+        let mut lexer = lexer::new(input.to_string());
+        let mut parser = new(&mut lexer);
+
+        let program = parser.parse_program();
+
+        assert!(parser.errors().is_empty());
+        assert_eq!(program.statements.len(), 1);
+        assert_eq!(
+            program.statements[0].expression().string_literal().value,
+            "hello world"
+        );
+    }
+
+    #[test]
+    fn test_string_literal_to_string() {
+        let tests = [
+            (r#"let s = "a" + b;"#, r#"let s = ("a" + b);"#),
+            (r#""tab\there\n""#, r#""tab\there\n""#),
+            (r#""say \"hi\" \\o/""#, r#""say \"hi\" \\o/""#),
+            (r#""\u{1F600}\u{7}""#, "\"\u{1F600}\\u{7}\""),
+        ];
+
+        for &test in tests.iter() {
+            let mut lexer = lexer::new(test.0.to_string());
+            let mut parser = new(&mut lexer);
+
+            let program = parser.parse_program();
+
+            assert!(parser.errors().is_empty());
+            assert_eq!(program.to_string(), test.1);
+        }
+    }
+
+    #[test]
+    fn test_string_literal_errors() {
+        let tests = [
+            (
+                "let a = 1;\nlet s = \"abc;",
+                ParseErrorKind::UnterminatedString,
+                "line 2, column 9: unterminated string",
+            ),
+            (
+                r#"let s = "a\qb""#,
+                ParseErrorKind::IllegalToken,
+                r"line 1, column 9: illegal token \q",
+            ),
+        ];
+
+        for test in tests.iter() {
+            let mut lexer = lexer::new(test.0.to_string());
+            let mut parser = new(&mut lexer);
+
+            parser.parse_program();
+
+            let errors = parser.errors();
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].kind, test.1);
+            assert_eq!(errors[0].to_string(), test.2);
+        }
+    }
+
+    #[test]
+    fn test_array_literal_parsing() {
+        let input = "[1, 2 * 2, 3 + 3, f(x), []]";
+
+        let mut lexer = lexer::new(input.to_string());
+        let mut parser = new(&mut lexer);
+
+        let program = parser.parse_program();
+
+        assert!(parser.errors().is_empty());
+
+        let array = program.statements[0].expression().array();
+        assert_eq!(array.elements.len(), 5);
+        assert_eq!(array.elements[0].integer_literal().value, 1);
+        assert_eq!(array.elements[1].to_string(), "(2 * 2)");
+        assert_eq!(array.elements[2].to_string(), "(3 + 3)");
+        assert_eq!(array.elements[3].call().function.identifier().value, "f");
+        assert!(array.elements[4].array().elements.is_empty());
+        assert_eq!(program.to_string(), "[1, (2 * 2), (3 + 3), f(x), []]");
+    }
+
+    #[test]
+    fn test_index_expression_parsing() {
+        let input = "myArray[1 + 1]";
+
+        let mut lexer = lexer::new(input.to_string());
+        let mut parser = new(&mut lexer);
+
+        let program = parser.parse_program();
+
+        assert!(parser.errors().is_empty());
+
+        let index = program.statements[0].expression().index();
+        assert_eq!(index.left.identifier().value, "myArray");
+        assert_eq!(index.index.to_string(), "(1 + 1)");
+    }
+
+    #[test]
+    fn test_nested_index_expression_parsing() {
+        let input = "matrix[i][[0, 1][j]]";
+
+        let mut lexer = lexer::new(input.to_string());
+        let mut parser = new(&mut lexer);
+
+        let program = parser.parse_program();
+
+        assert!(parser.errors().is_empty());
+
+        let outer = program.statements[0].expression().index();
+        assert_eq!(outer.left.index().left.identifier().value, "matrix");
+        assert_eq!(outer.left.index().index.identifier().value, "i");
+        assert_eq!(outer.index.index().left.array().elements.len(), 2);
+        assert_eq!(program.to_string(), "((matrix[i])[([0, 1][j])])");
+    }
+
+    #[test]
+    fn test_hash_literal_parsing() {
+        let tests = [
+            (
+                r#"{"one": 1, "two": 2, "three": 3}"#,
+                r#"{"one": 1, "two": 2, "three": 3}"#,
+            ),
+            ("{}", "{}"),
+            (
+                r#"{"one": 0 + 1, 2: 10 - 8, true: 15 / 5, x: [1]}"#,
+                r#"{"one": (0 + 1), 2: (10 - 8), true: (15 / 5), x: [1]}"#,
+            ),
+            (r#"{"a": {"b": 1},}"#, r#"{"a": {"b": 1}}"#),
+            (
+                "let config = {1 + 1: f(x)};",
+                "let config = {(1 + 1): f(x)};",
+            ),
+        ];
+
+        for &test in tests.iter() {
+            let mut lexer = lexer::new(test.0.to_string());
+            let mut parser = new(&mut lexer);
+
+            let program = parser.parse_program();
+
+            assert!(parser.errors().is_empty(), "{}", test.0);
+            assert_eq!(program.statements.len(), 1);
+            assert_eq!(program.to_string(), test.1);
+        }
+    }
+
+    #[test]
+    fn test_hash_literal_pairs() {
+        let input = r#"{"one": 1, true: 2}"#;
+
+        let mut lexer = lexer::new(input.to_string());
+        let mut parser = new(&mut lexer);
+
+        let program = parser.parse_program();
+
+        let hash = program.statements[0].expression().hash();
+        assert_eq!(hash.pairs.len(), 2);
+        assert_eq!(hash.pairs[0].0.string_literal().value, "one");
+        assert_eq!(hash.pairs[0].1.integer_literal().value, 1);
+        assert!(hash.pairs[1].0.boolean().value);
+        assert_eq!(hash.pairs[1].1.integer_literal().value, 2);
+    }
+
+    #[test]
+    fn test_hash_and_block_statements() {
+        let input = r#"if (x) { {"a": 1}["a"] } else { {} }"#;
+
+        let mut lexer = lexer::new(input.to_string());
+        let mut parser = new(&mut lexer);
+
+        let program = parser.parse_program();
+
+        assert!(parser.errors().is_empty());
+
+        let statement = program.statements[0].expression().if_statement();
+        let index = statement.consequence.statements[0].expression().index();
+        assert_eq!(index.left.hash().pairs.len(), 1);
+        assert_eq!(index.index.string_literal().value, "a");
+
+        let alternative = statement.alternative.as_ref().unwrap();
+        assert!(alternative.statements[0]
+            .expression()
+            .hash()
+            .pairs
+            .is_empty());
+    }
 
+    #[test]
+    fn test_hash_literal_errors() {
+        let input = r#"{"a" 1}"#;
+
+        let mut lexer = lexer::new(input.to_string());
+        let mut parser = new(&mut lexer);
+
+        parser.parse_program();
+
+        let error = &parser.errors()[0];
+        assert_eq!(error.kind, ParseErrorKind::UnexpectedToken);
+        assert_eq!(error.expected, Some(TokenKind::Colon));
+        assert_eq!(error.found.token_type, TokenKind::Int);
+    }
+
+    #[test]
+    fn test_assign_expression_parsing() {
+        let input = "arr[0] *= 5;";
+
+        let mut lexer = lexer::new(input.to_string());
+        let mut parser = new(&mut lexer);
+
+        let program = parser.parse_program();
+
+        assert!(parser.errors().is_empty());
+
+        let assign = program.statements[0].expression().assign();
+        assert_eq!(assign.operator, "*=");
+        assert_eq!(assign.target.index().left.to_string(), "arr");
+        assert_eq!(assign.value.integer_literal().value, 5);
+    }
+
+    #[test]
+    fn test_invalid_assignment_targets() {
+        let tests = [
+            (
+                "1 = 2",
+                "line 1, column 3: left-hand side of = is not assignable",
+            ),
+            (
+                "f() += 1",
+                "line 1, column 5: left-hand side of += is not assignable",
+            ),
+            (
+                "(a + b) = c",
+                "line 1, column 9: left-hand side of = is not assignable",
+            ),
+            (
+                "[1][0] = 2",
+                "line 1, column 8: left-hand side of = is not assignable",
+            ),
+        ];
+
+        for &test in tests.iter() {
+            let mut lexer = lexer::new(test.0.to_string());
+            let mut parser = new(&mut lexer);
+
+            let program = parser.parse_program();
+
+            let errors = parser.errors();
+            assert_eq!(errors.len(), 1, "{}", test.0);
+            assert_eq!(errors[0].kind, ParseErrorKind::InvalidAssignmentTarget);
+            assert_eq!(errors[0].to_string(), test.1);
+            assert!(program.statements.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_comments_are_ignored() {
+        let input = "let x = 5; // five\n/* the /* answer */ */ x * 2";
+
+        let mut lexer = lexer::new(input.to_string());
+        let mut parser = new(&mut lexer);
+
+        let program = parser.parse_program();
+
+        assert!(parser.errors().is_empty());
+        assert_eq!(program.to_string(), "let x = 5; (x * 2)");
+    }
+
+    #[test]
+    fn test_unterminated_comment_error() {
+        let input = "x; /* never closed";
+
+        let mut lexer = lexer::new(input.to_string());
+        let mut parser = new(&mut lexer);
+
+        parser.parse_program();
+
+        let errors = parser.errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, ParseErrorKind::UnterminatedComment);
+        assert_eq!(
+            errors[0].to_string(),
+            "line 1, column 4: unterminated block comment"
+        );
+    }
+
+    #[test]
+    fn test_logical_expression_parsing() {
+        let input = "if (x >= 0 && x < 10) { x }";
+
+        let mut lexer = lexer::new(input.to_string());
+        let mut parser = new(&mut lexer);
+
+        let program = parser.parse_program();
+
+        assert!(parser.errors().is_empty());
+
+        let condition = &program.statements[0].expression().if_statement().condition;
+        let logical = condition.logical();
+        assert_eq!(logical.operator, "&&");
+        assert_eq!(logical.left.infix().operator, ">=");
+        assert_eq!(logical.right.infix().operator, "<");
+    }
+
+    #[test]
+    fn test_float_literal_expression() {
+        let tests = [
+            ("3.25;", 3.25, "3.25"),
+            ("0.5", 0.5, "0.5"),
+            ("1e-9", 1e-9, "1e-9"),
+            ("2.5E+3", 2500.0, "2500.0"),
+            ("7e2", 700.0, "700.0"),
+        ];
+
+        for &test in tests.iter() {
+            let mut lexer = lexer::new(test.0.to_string());
+            let mut parser = new(&mut lexer);
+
+            let program = parser.parse_program();
+
+            assert!(parser.errors().is_empty());
+            assert_eq!(program.statements.len(), 1);
+
+            let float = program.statements[0].expression().float_literal();
+            assert_eq!(float.value, test.1);
+            assert_eq!(program.to_string(), test.2);
+        }
+    }
+
+    #[test]
+    fn test_radix_integer_literals() {
+        let tests = [
+            ("0xFF", 255),
+            ("0Xff", 255),
+            ("0b1010", 10),
+            ("0o755", 493),
+            ("1_000_000", 1_000_000),
+            ("0xFFFF_FFFF", 0xFFFF_FFFF),
+            ("0b_1111_0000", 0b1111_0000),
+            ("9223372036854775807", i64::MAX),
+            ("0x7FFFFFFFFFFFFFFF", i64::MAX),
+            ("007", 7),
+        ];
+
+        for &test in tests.iter() {
+            let mut lexer = lexer::new(test.0.to_string());
+            let mut parser = new(&mut lexer);
+
+            let program = parser.parse_program();
+
+            assert!(parser.errors().is_empty(), "{}", test.0);
+
+            let integer = program.statements[0].expression().integer_literal();
+            assert_eq!(integer.value, test.1);
+            assert_eq!(integer.token_literal(), test.0);
+        }
+    }
+
+    #[test]
+    fn test_invalid_number_literals() {
+        let tests = [
+            (
+                "9223372036854775808",
+                ParseErrorKind::NumberOutOfRange,
+                "line 1, column 1: number literal 9223372036854775808 out of range",
+            ),
+            (
+                "let x = 0x1_0000_0000_0000_0000",
+                ParseErrorKind::NumberOutOfRange,
+                "line 1, column 9: number literal 0x1_0000_0000_0000_0000 out of range",
+            ),
+            (
+                "1e999",
+                ParseErrorKind::NumberOutOfRange,
+                "line 1, column 1: number literal 1e999 out of range",
+            ),
+            (
+                "0b102",
+                ParseErrorKind::InvalidNumberLiteral,
+                "line 1, column 1: invalid number literal 0b102",
+            ),
+            (
+                "0x",
+                ParseErrorKind::InvalidNumberLiteral,
+                "line 1, column 1: invalid number literal 0x",
+            ),
+            (
+                "0o8",
+                ParseErrorKind::InvalidNumberLiteral,
+                "line 1, column 1: invalid number literal 0o8",
+            ),
+        ];
+
+        for test in tests.iter() {
+            let mut lexer = lexer::new(test.0.to_string());
+            let mut parser = new(&mut lexer);
+
+            parser.parse_program();
+
+            let errors = parser.errors();
+            assert_eq!(errors.len(), 1, "{}", test.0);
+            assert_eq!(errors[0].kind, test.1);
+            assert_eq!(errors[0].to_string(), test.2);
+        }
+    }
+
+    #[test]
+    fn test_while_statement_parsing() {
+        let input = "while (x < 10) { if (x == 5) { break; } continue; x }";
+
+        let mut lexer = lexer::new(input.to_string());
+        let mut parser = new(&mut lexer);
+
+        let program = parser.parse_program();
+
+        assert!(parser.errors().is_empty());
+        assert_eq!(program.statements.len(), 1);
+
+        let statement = program.statements[0].while_statement();
+        assert_eq!(statement.condition.to_string(), "(x < 10)");
+        assert_eq!(statement.body.statements.len(), 3);
+
+        let consequence = &statement.body.statements[0]
+            .expression()
+            .if_statement()
+            .consequence;
+        match consequence.statements[0] {
+            ast::Statements::Break(_) => {}
+            _ => unreachable!(),
+        }
+        match statement.body.statements[1] {
+            ast::Statements::Continue(_) => {}
+            _ => unreachable!(),
+        }
+
+        assert_eq!(
+            program.to_string(),
+            "while (x < 10) { if (x == 5) { break; }; continue; x }"
+        );
+    }
+
+    #[test]
+    fn test_nested_loops() {
+        let input = "while (a) { while (b) { break } continue }";
+
+        let mut lexer = lexer::new(input.to_string());
+        let mut parser = new(&mut lexer);
+
+        let program = parser.parse_program();
+
+        assert!(parser.errors().is_empty());
+        assert_eq!(
+            program.to_string(),
+            "while (a) { while (b) { break; } continue; }"
+        );
+    }
+
+    #[test]
+    fn test_while_statement_trailing_semicolon() {
+        let input = "while (a) { b }; while (c) { while (d) {}; };";
+
+        let mut lexer = lexer::new(input.to_string());
+        let mut parser = new(&mut lexer);
+
+        let program = parser.parse_program();
+
+        assert!(parser.errors().is_empty());
+        assert_eq!(
+            program.to_string(),
+            "while (a) { b } while (c) { while (d) {} }"
+        );
+    }
+
+    #[test]
+    fn test_for_statement_parsing() {
+        let tests = [
+            (
+                "for (x in [1, 2]) { x }",
+                vec!["x"],
+                "[1, 2]",
+                "for (x in [1, 2]) { x }",
+            ),
+            (
+                "for (k, v in map) { if (v) { break; } }",
+                vec!["k", "v"],
+                "map",
+                "for (k, v in map) { if (v) { break; } }",
+            ),
+        ];
+
+        for test in tests.iter() {
+            let mut lexer = lexer::new(test.0.to_string());
+            let mut parser = new(&mut lexer);
+
+            let program = parser.parse_program();
+
+            assert!(parser.errors().is_empty(), "{}", test.0);
+            assert_eq!(program.statements.len(), 1);
+
+            let statement = program.statements[0].for_statement();
+            let variables: Vec<&str> = statement
+                .variables
+                .iter()
+                .map(|v| v.value.as_str())
+                .collect();
+            assert_eq!(variables, test.1);
+            assert_eq!(statement.iterable.to_string(), test.2);
+            assert_eq!(program.to_string(), test.3);
+        }
+    }
+
+    #[test]
+    fn test_for_statement_trailing_semicolon() {
+        let input = "for (x in xs) { x }; for (k, v in m) { for (y in x) {}; };";
+
+        let mut lexer = lexer::new(input.to_string());
+        let mut parser = new(&mut lexer);
+
+        let program = parser.parse_program();
+
+        assert!(parser.errors().is_empty());
+        assert_eq!(
+            program.to_string(),
+            "for (x in xs) { x } for (k, v in m) { for (y in x) {} }"
+        );
+    }
+
+    #[test]
+    fn test_for_statement_errors() {
+        let tests = [
+            (
+                "for x in y { }",
+                "line 1, column 5: expected ( but got IDENT",
+            ),
+            (
+                "for (x y) { }",
+                "line 1, column 8: expected IN but got IDENT",
+            ),
+            (
+                "for (a, b, c in y) { }",
+                "line 1, column 10: expected IN but got ,",
+            ),
+        ];
+
+        for &test in tests.iter() {
+            let mut lexer = lexer::new(test.0.to_string());
+            let mut parser = new(&mut lexer);
+
+            parser.parse_program();
+
+            let errors = parser.errors();
+            assert!(!errors.is_empty(), "{}", test.0);
+            assert_eq!(errors[0].to_string(), test.1);
+        }
+    }
+
+    #[test]
+    fn test_loop_control_outside_loop() {
+        let tests = [
+            ("break;", "line 1, column 1: break outside of a loop"),
+            (
+                "if (x) { continue; }",
+                "line 1, column 10: continue outside of a loop",
+            ),
+            (
+                "while (x) { let f = fn() { break; }; }",
+                "line 1, column 28: break outside of a loop",
+            ),
+        ];
+
+        for &test in tests.iter() {
+            let mut lexer = lexer::new(test.0.to_string());
+            let mut parser = new(&mut lexer);
+
+            parser.parse_program();
+
+            let errors = parser.errors();
+            assert_eq!(errors.len(), 1, "{}", test.0);
+            assert_eq!(errors[0].kind, ParseErrorKind::OutsideLoop);
+            assert_eq!(errors[0].to_string(), test.1);
+        }
     }
 }
@@ -0,0 +1,645 @@
+use crate::parser::{self, ASSIGN, CALL, LOWEST, PREFIX};
+use crate::token::TokenKind;
+use crate::{ast, token};
+use std::collections::HashMap;
+
+const INDENT: &str = "    ";
+
+/// Prints `program` as canonical Monkey source: one statement per line,
+/// blocks indented by four spaces and only the parentheses needed to parse
+/// back to the same tree.
+pub fn print(program: &ast::Program) -> String {
+    let mut printer = Printer::new(0);
+    printer.statements(&program.statements, usize::MAX);
+    printer.output
+}
+
+/// Like `print`, but also re-emits the comments in `tokens`, the stream
+/// produced by `lexer::with_comments` for the source of `program`. Comments
+/// on the same line as the code before them stay trailing; every other
+/// comment between statements goes on its own line ahead of the statement
+/// that follows it. Comments inside an expression stay where they are, and a
+/// list holding a line comment keeps one item per line. Single
+/// blank lines between statements are kept.
+pub fn print_with_comments(program: &ast::Program, tokens: &[token::Token]) -> String {
+    let mut printer = Printer::new(0);
+    // Each open bracket with whether it is one a comment can belong to. Only
+    // a `(` that starts a call is: the printer may drop grouping parentheses,
+    // and leaves the comments in the others where a list would.
+    let mut open_brackets: Vec<(usize, bool)> = vec![];
+    let mut previous: Option<&token::Token> = None;
+
+    for token in tokens {
+        match token.token_type {
+            TokenKind::Comment => printer.comments.push(Comment {
+                text: token.literal.trim_end().to_string(),
+                span: token.span,
+                trailing: matches!(previous, Some(p) if p.span.line == token.span.line),
+                bracket: open_brackets
+                    .iter()
+                    .rev()
+                    .find(|(_, owns)| *owns)
+                    .map(|(start, _)| *start),
+            }),
+            TokenKind::Eof => {}
+            kind => {
+                match kind {
+                    TokenKind::LBrace | TokenKind::LBracket => {
+                        open_brackets.push((token.span.start, true))
+                    }
+                    TokenKind::LParen => {
+                        let call = matches!(previous, Some(p) if ends_operand(p.token_type));
+                        open_brackets.push((token.span.start, call))
+                    }
+                    TokenKind::RBrace | TokenKind::RBracket | TokenKind::RParen => {
+                        if let Some((start, _)) = open_brackets.pop() {
+                            printer.closers.insert(start, token.span);
+                        }
+                    }
+                    _ => {}
+                }
+                previous = Some(token);
+                printer.code.push(token.span);
+            }
+        }
+    }
+
+    printer.keep_blank_lines = true;
+    printer.keep_literals = true;
+    printer.statements(&program.statements, usize::MAX);
+    printer.leading_comments(usize::MAX);
+    printer.output
+}
+
+struct Comment {
+    text: String,
+    span: token::Span,
+    trailing: bool,
+    // Start offset of the innermost bracket the comment sits in.
+    bracket: Option<usize>,
+}
+
+struct Printer {
+    output: String,
+    indent: usize,
+    comments: Vec<Comment>,
+    next_comment: usize,
+    // Spans of every non-comment token, in source order.
+    code: Vec<token::Span>,
+    // Start offset of each `{`, `[` or `(` mapped to the span of the bracket
+    // that closes it.
+    closers: HashMap<usize, token::Span>,
+    keep_blank_lines: bool,
+    // Print numbers as spelled in the source rather than from their value.
+    keep_literals: bool,
+    // The source line the last printed statement or comment ended on, or 0
+    // at the start of a block.
+    last_line: usize,
+}
+
+impl Printer {
+    fn new(indent: usize) -> Printer {
+        Printer {
+            output: String::new(),
+            indent,
+            comments: vec![],
+            next_comment: 0,
+            code: vec![],
+            closers: HashMap::new(),
+            keep_blank_lines: false,
+            keep_literals: false,
+            last_line: 0,
+        }
+    }
+
+    // Prints `statements`, the contents of a block whose closing brace starts
+    // at `end`.
+    fn statements(&mut self, statements: &[ast::Statements], end: usize) {
+        for (i, statement) in statements.iter().enumerate() {
+            let token = statement.token();
+
+            // A comment just inside the parentheses around a leading grouped
+            // expression leads the statement too.
+            let start = match statement {
+                ast::Statements::Expression(s) => expression_start(&s.expression),
+                _ => token.span.start,
+            };
+            self.leading_comments(start);
+            self.blank_line(token.span.line);
+            self.write_indent();
+            self.statement(statement);
+
+            // A trailing `if` needs no `;` unless the next statement starts
+            // with a token that also has an infix parser, such as `(`, `[` or
+            // `-`, which would otherwise continue the expression.
+            if let ast::Statements::Expression(s) = statement {
+                let semicolon = match s.expression {
+                    ast::Expressions::If(_) => {
+                        matches!(statements.get(i + 1), Some(next) if starts_with_infix(next))
+                    }
+                    _ => true,
+                };
+                if semicolon {
+                    self.output.push(';');
+                }
+            }
+
+            let boundary = statements
+                .get(i + 1)
+                .map_or(end, |next| next.token().span.start);
+            self.trailing_comments(boundary);
+            self.output.push('\n');
+        }
+    }
+
+    // Writes every pending comment that starts before `offset` on a line of
+    // its own.
+    fn leading_comments(&mut self, offset: usize) {
+        while let Some(comment) = self.comments.get(self.next_comment) {
+            if comment.span.start >= offset {
+                break;
+            }
+
+            let (line, text) = (comment.span.line, comment.text.clone());
+            self.blank_line(line);
+            self.write_indent();
+            self.output.push_str(&text);
+            self.output.push('\n');
+            self.last_line = line + text.matches('\n').count();
+            self.next_comment += 1;
+        }
+    }
+
+    // Writes every pending comment that starts before `offset` in the middle
+    // of a line. A line comment ends the line, and the code after it goes on
+    // the next line at `indent`.
+    fn inline_comments(&mut self, offset: usize, indent: usize) {
+        while let Some(comment) = self.comments.get(self.next_comment) {
+            if comment.span.start >= offset {
+                break;
+            }
+
+            let text = comment.text.clone();
+            if !self.output.ends_with(|c: char| "([{".contains(c)) {
+                self.space();
+            }
+            self.output.push_str(&text);
+            if text.starts_with("//") {
+                self.output.push('\n');
+                for _ in 0..indent {
+                    self.output.push_str(INDENT);
+                }
+            } else {
+                self.output.push(' ');
+            }
+            self.next_comment += 1;
+        }
+    }
+
+    // Writes the pending comments that start before `offset`, the start of a
+    // closing bracket, so that they stay inside the brackets.
+    fn comments_before_closer(&mut self, offset: usize) {
+        self.inline_comments(offset, self.indent + 1);
+        if self.output.ends_with("*/ ") {
+            self.output.pop();
+        }
+    }
+
+    // Separates the next token from the code before it, unless something
+    // already does.
+    fn space(&mut self) {
+        if !self.output.is_empty() && !self.output.ends_with(char::is_whitespace) {
+            self.output.push(' ');
+        }
+    }
+
+    // Appends the comments that belong to the statement or list item just
+    // printed: those inside it and those following it on its last line.
+    // `boundary` is where the next statement or item, or the enclosing
+    // bracket, starts.
+    fn trailing_comments(&mut self, boundary: usize) {
+        let last_code = self.code_before(boundary);
+        let statement_end = last_code.map_or(0, |span| span.end);
+        if let Some(span) = last_code {
+            self.last_line = span.line;
+        }
+
+        // Whatever follows a line comment is left to `leading_comments`.
+        while let Some(comment) = self.comments.get(self.next_comment) {
+            let inside = comment.span.start < statement_end;
+            if comment.span.start >= boundary || !(inside || comment.trailing) {
+                break;
+            }
+
+            let (line, text) = (comment.span.line, comment.text.clone());
+            self.output.push(' ');
+            self.output.push_str(&text);
+            self.last_line = self.last_line.max(line + text.matches('\n').count());
+            self.next_comment += 1;
+
+            if text.starts_with("//") {
+                break;
+            }
+        }
+    }
+
+    // The span of the last code token that starts before `offset`.
+    fn code_before(&self, offset: usize) -> Option<token::Span> {
+        match self.code.binary_search_by_key(&offset, |span| span.start) {
+            Ok(i) | Err(i) => i.checked_sub(1).map(|i| self.code[i]),
+        }
+    }
+
+    // The span of the first code token that starts after `offset`.
+    fn code_after(&self, offset: usize) -> Option<token::Span> {
+        let next = offset + 1;
+        match self.code.binary_search_by_key(&next, |span| span.start) {
+            Ok(i) | Err(i) => self.code.get(i).cloned(),
+        }
+    }
+
+    fn blank_line(&mut self, line: usize) {
+        if self.keep_blank_lines && self.last_line > 0 && line > self.last_line + 1 {
+            self.output.push('\n');
+        }
+    }
+
+    fn statement(&mut self, statement: &ast::Statements) {
+        match statement {
+            ast::Statements::Let(s) => {
+                self.output.push_str("let ");
+                self.output.push_str(&s.name.value);
+                self.output.push_str(" = ");
+                self.expression(&s.value, LOWEST);
+                self.output.push(';');
+            }
+            ast::Statements::Return(s) => {
+                self.output.push_str("return");
+                if let Some(value) = &s.return_value {
+                    self.output.push(' ');
+                    self.expression(value, LOWEST);
+                }
+                self.output.push(';');
+            }
+            ast::Statements::Expression(s) => self.expression(&s.expression, LOWEST),
+            ast::Statements::While(s) => {
+                self.output.push_str("while (");
+                self.expression(&s.condition, LOWEST);
+                self.close_header(&s.body);
+                self.block(&s.body);
+            }
+            ast::Statements::For(s) => {
+                let variables: Vec<&str> = s.variables.iter().map(|v| v.value.as_str()).collect();
+                self.output.push_str("for (");
+                self.output.push_str(&variables.join(", "));
+                self.output.push_str(" in ");
+                self.expression(&s.iterable, LOWEST);
+                self.close_header(&s.body);
+                self.block(&s.body);
+            }
+            ast::Statements::Break(_) => self.output.push_str("break;"),
+            ast::Statements::Continue(_) => self.output.push_str("continue;"),
+        }
+    }
+
+    // Ends the header of a `while`, `for` or `if` whose body is `body`,
+    // keeping comments that come before its `)` inside the parentheses.
+    fn close_header(&mut self, body: &ast::BlockStatement) {
+        if let Some(paren) = self.code_before(body.token.span.start) {
+            self.comments_before_closer(paren.start);
+        }
+        self.output.push_str(") ");
+    }
+
+    fn block(&mut self, block: &ast::BlockStatement) {
+        self.inline_comments(block.token.span.start, self.indent);
+
+        let end = self
+            .closers
+            .get(&block.token.span.start)
+            .map_or(usize::MAX, |span| span.start);
+        let has_comments = matches!(
+            self.comments.get(self.next_comment),
+            Some(comment) if comment.span.start < end
+        );
+
+        if block.statements.is_empty() && !has_comments {
+            self.output.push_str("{}");
+            return;
+        }
+
+        self.output.push_str("{\n");
+        self.indent += 1;
+        self.last_line = 0;
+        self.statements(&block.statements, end);
+        self.leading_comments(end);
+        self.indent -= 1;
+        self.write_indent();
+        self.output.push('}');
+    }
+
+    // Prints `expression`, wrapped in parentheses when it binds looser than
+    // `precedence`, the tightest binding its position requires.
+    fn expression(&mut self, expression: &ast::Expressions, precedence: u8) {
+        self.inline_comments(expression_start(expression), self.indent + 1);
+
+        let grouped = expression_precedence(expression) < precedence;
+        if grouped {
+            self.output.push('(');
+        }
+
+        match expression {
+            ast::Expressions::Identifier(i) => self.output.push_str(&i.value),
+            ast::Expressions::IntegerLiteral(i) if self.keep_literals => {
+                self.output.push_str(&i.token.literal)
+            }
+            ast::Expressions::IntegerLiteral(i) => self.output.push_str(&i.value.to_string()),
+            ast::Expressions::FloatLiteral(f) if self.keep_literals => {
+                self.output.push_str(&f.token.literal)
+            }
+            ast::Expressions::FloatLiteral(f) => self.output.push_str(&format!("{:?}", f.value)),
+            ast::Expressions::StringLiteral(s) => self.output.push_str(&s.to_string()),
+            ast::Expressions::Boolean(b) => self.output.push_str(&b.value.to_string()),
+            ast::Expressions::Prefix(p) => {
+                self.output.push_str(&p.operator);
+                self.expression(&p.right, PREFIX);
+            }
+            ast::Expressions::Infix(i) => {
+                self.binary(&i.left, &i.token, &i.operator, &i.right);
+            }
+            ast::Expressions::Logical(l) => {
+                self.binary(&l.left, &l.token, &l.operator, &l.right);
+            }
+            ast::Expressions::Assign(a) => {
+                self.expression(&a.target, ASSIGN + 1);
+                self.inline_comments(a.token.span.start, self.indent + 1);
+                self.space();
+                self.output.push_str(&a.operator);
+                self.output.push(' ');
+                self.expression(&a.value, ASSIGN);
+            }
+            ast::Expressions::If(i) => {
+                self.output.push_str("if (");
+                self.expression(&i.condition, LOWEST);
+                self.close_header(&i.consequence);
+                self.block(&i.consequence);
+                if let Some(alternative) = &i.alternative {
+                    // Comments between `}` and `else` stay ahead of the `else`.
+                    if let Some(else_token) = self.code_before(alternative.token.span.start) {
+                        self.inline_comments(else_token.start, self.indent);
+                    }
+                    self.space();
+                    self.output.push_str("else ");
+                    self.block(alternative);
+                }
+            }
+            ast::Expressions::Function(f) => {
+                let parameters: Vec<&str> = f.parameters.iter().map(|p| p.value.as_str()).collect();
+                let open = self.code_after(f.token.span.start);
+                self.output.push_str("fn");
+                if let Some(open) = open {
+                    self.inline_comments(open.start, self.indent + 1);
+                }
+                self.output.push('(');
+                self.output.push_str(&parameters.join(", "));
+                if let Some(close) = open.and_then(|span| self.closers.get(&span.start).cloned()) {
+                    self.comments_before_closer(close.start);
+                }
+                self.output.push_str(") ");
+                self.block(&f.body);
+            }
+            ast::Expressions::Call(c) => {
+                self.expression(&c.function, CALL);
+                self.inline_comments(c.token.span.start, self.indent + 1);
+                self.output.push('(');
+                self.list(&c.token, &c.arguments);
+                self.output.push(')');
+            }
+            ast::Expressions::Index(i) => {
+                self.expression(&i.left, CALL);
+                self.inline_comments(i.token.span.start, self.indent + 1);
+                self.output.push('[');
+                self.expression(&i.index, LOWEST);
+                if let Some(close) = self.closers.get(&i.token.span.start).cloned() {
+                    self.comments_before_closer(close.start);
+                }
+                self.output.push(']');
+            }
+            ast::Expressions::Array(a) => {
+                self.output.push('[');
+                self.list(&a.token, &a.elements);
+                self.output.push(']');
+            }
+            ast::Expressions::Hash(h) => {
+                self.output.push('{');
+                self.items(
+                    &h.token,
+                    &h.pairs,
+                    |(key, _)| expression_start(key),
+                    |printer, (key, value)| {
+                        printer.expression(key, LOWEST);
+                        printer.output.push_str(": ");
+                        printer.expression(value, LOWEST);
+                    },
+                );
+                self.output.push('}');
+            }
+        }
+
+        if grouped {
+            // The source had these parentheses too; keep comments that
+            // come before its `)` inside them.
+            let open = self.code_before(expression_start(expression));
+            if let Some(close) = open.and_then(|span| self.closers.get(&span.start).cloned()) {
+                self.comments_before_closer(close.start);
+            }
+            self.output.push(')');
+        }
+    }
+
+    fn binary(
+        &mut self,
+        left: &ast::Expressions,
+        token: &token::Token,
+        operator: &str,
+        right: &ast::Expressions,
+    ) {
+        let (left_precedence, right_precedence) = operand_precedences(token.token_type);
+
+        self.expression(left, left_precedence);
+        self.inline_comments(token.span.start, self.indent + 1);
+        self.space();
+        self.output.push_str(operator);
+        self.output.push(' ');
+        self.expression(right, right_precedence);
+    }
+
+    fn list(&mut self, open: &token::Token, expressions: &[ast::Expressions]) {
+        self.items(
+            open,
+            expressions,
+            expression_start,
+            |printer, expression| printer.expression(expression, LOWEST),
+        );
+    }
+
+    // Prints the comma-separated `items` of the list opened by `open`. A list
+    // with a line comment directly inside it is printed one item per line so
+    // that each comment stays beside its item.
+    fn items<T>(
+        &mut self,
+        open: &token::Token,
+        items: &[T],
+        start: fn(&T) -> usize,
+        print: fn(&mut Printer, &T),
+    ) {
+        let close = self.closers.get(&open.span.start).cloned();
+        let end = close.map_or(usize::MAX, |span| span.start);
+        let multiline = self.comments[self.next_comment..]
+            .iter()
+            .take_while(|comment| comment.span.start < end)
+            .any(|comment| {
+                comment.bracket == Some(open.span.start) && comment.text.starts_with("//")
+            });
+
+        if !multiline {
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    self.output.push_str(", ");
+                }
+                print(self, item);
+            }
+            if close.is_some() {
+                self.comments_before_closer(end);
+            }
+            return;
+        }
+
+        self.output.push('\n');
+        self.indent += 1;
+        self.last_line = open.span.line;
+        for (i, item) in items.iter().enumerate() {
+            self.leading_comments(start(item));
+            self.write_indent();
+            print(self, item);
+            if i + 1 < items.len() {
+                self.output.push(',');
+            }
+            self.trailing_comments(items.get(i + 1).map_or(end, start));
+            self.output.push('\n');
+        }
+        self.leading_comments(end);
+        self.indent -= 1;
+        self.write_indent();
+    }
+
+    fn write_indent(&mut self) {
+        for _ in 0..self.indent {
+            self.output.push_str(INDENT);
+        }
+    }
+}
+
+fn expression_precedence(expression: &ast::Expressions) -> u8 {
+    match expression {
+        ast::Expressions::Prefix(_) => PREFIX,
+        ast::Expressions::Infix(i) => parser::precedence(i.token.token_type),
+        ast::Expressions::Logical(l) => parser::precedence(l.token.token_type),
+        ast::Expressions::Assign(_) => ASSIGN,
+        ast::Expressions::Call(_) | ast::Expressions::Index(_) => CALL,
+        _ => u8::MAX,
+    }
+}
+
+// The start of the first token of `expression`, leaving aside any parentheses
+// around it.
+fn expression_start(expression: &ast::Expressions) -> usize {
+    match expression {
+        ast::Expressions::Infix(i) => expression_start(&i.left),
+        ast::Expressions::Logical(l) => expression_start(&l.left),
+        ast::Expressions::Assign(a) => expression_start(&a.target),
+        ast::Expressions::Call(c) => expression_start(&c.function),
+        ast::Expressions::Index(i) => expression_start(&i.left),
+        ast::Expressions::Identifier(i) => i.token.span.start,
+        ast::Expressions::IntegerLiteral(i) => i.token.span.start,
+        ast::Expressions::FloatLiteral(f) => f.token.span.start,
+        ast::Expressions::StringLiteral(s) => s.token.span.start,
+        ast::Expressions::Boolean(b) => b.token.span.start,
+        ast::Expressions::Prefix(p) => p.token.span.start,
+        ast::Expressions::If(i) => i.token.span.start,
+        ast::Expressions::Function(f) => f.token.span.start,
+        ast::Expressions::Array(a) => a.token.span.start,
+        ast::Expressions::Hash(h) => h.token.span.start,
+    }
+}
+
+// Whether a token of this kind can end an operand, so that a `(` after it
+// starts a call.
+fn ends_operand(kind: TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::Ident
+            | TokenKind::Int
+            | TokenKind::Float
+            | TokenKind::String
+            | TokenKind::True
+            | TokenKind::False
+            | TokenKind::RParen
+            | TokenKind::RBracket
+            | TokenKind::RBrace
+    )
+}
+
+// The precedences the left and right operands of a binary operator must have
+// to print without parentheses. The operand on the associative side may share
+// the operator's precedence; the other side has to bind strictly tighter.
+fn operand_precedences(operator: TokenKind) -> (u8, u8) {
+    let precedence = parser::precedence(operator);
+
+    if parser::is_right_associative(operator) {
+        (precedence + 1, precedence)
+    } else {
+        (precedence, precedence + 1)
+    }
+}
+
+// Whether `statement` prints starting with a token that also has an infix
+// parser, and so would continue an expression printed just before it.
+fn starts_with_infix(statement: &ast::Statements) -> bool {
+    let first = match statement {
+        ast::Statements::Expression(s) => first_token(&s.expression, LOWEST),
+        _ => statement.token().token_type,
+    };
+
+    parser::precedence(first) > LOWEST
+}
+
+// The kind of the first token `expression` prints as in a position that
+// requires `precedence`.
+fn first_token(expression: &ast::Expressions, precedence: u8) -> TokenKind {
+    if expression_precedence(expression) < precedence {
+        return TokenKind::LParen;
+    }
+
+    match expression {
+        ast::Expressions::Infix(i) => {
+            first_token(&i.left, operand_precedences(i.token.token_type).0)
+        }
+        ast::Expressions::Logical(l) => {
+            first_token(&l.left, operand_precedences(l.token.token_type).0)
+        }
+        ast::Expressions::Assign(a) => first_token(&a.target, ASSIGN + 1),
+        ast::Expressions::Call(c) => first_token(&c.function, CALL),
+        ast::Expressions::Index(i) => first_token(&i.left, CALL),
+        ast::Expressions::Identifier(i) => i.token.token_type,
+        ast::Expressions::IntegerLiteral(i) => i.token.token_type,
+        ast::Expressions::FloatLiteral(f) => f.token.token_type,
+        ast::Expressions::StringLiteral(s) => s.token.token_type,
+        ast::Expressions::Boolean(b) => b.token.token_type,
+        ast::Expressions::Prefix(p) => p.token.token_type,
+        ast::Expressions::If(i) => i.token.token_type,
+        ast::Expressions::Function(f) => f.token.token_type,
+        ast::Expressions::Array(_) => TokenKind::LBracket,
+        ast::Expressions::Hash(_) => TokenKind::LBrace,
+    }
+}
@@ -0,0 +1,138 @@
+#[cfg(test)]
+mod tests {
+    use crate::pretty::*;
+    use crate::{lexer, parser};
+
+    fn pretty(input: &str) -> String {
+        let mut lexer = lexer::new(input.to_string());
+        let mut parser = parser::new(&mut lexer);
+        let program = parser.parse_program();
+
+        assert!(parser.errors().is_empty(), "{}", input);
+
+        print(&program)
+    }
+
+    fn parse_display(input: &str) -> String {
+        let mut lexer = lexer::new(input.to_string());
+        let mut parser = parser::new(&mut lexer);
+
+        parser.parse_program().to_string()
+    }
+
+    #[test]
+    fn test_print_program() {
+        let input = r#"let add=fn(a,b){return a+b;};
+let xs=[1,2,3];let total=0;
+for(x in xs){if(x==2){continue;}total+=x;}
+while (total>0) { total -= 1; if (total < 2) { break } }
+if(total){"yes"}else{{"k": [true, 2.5]}["k"]}
+return;"#;
+
+        let expected = r#"let add = fn(a, b) {
+    return a + b;
+};
+let xs = [1, 2, 3];
+let total = 0;
+for (x in xs) {
+    if (x == 2) {
+        continue;
+    }
+    total += x;
+}
+while (total > 0) {
+    total -= 1;
+    if (total < 2) {
+        break;
+    }
+}
+if (total) {
+    "yes";
+} else {
+    {"k": [true, 2.5]}["k"];
+}
+return;
+"#;
+
+        assert_eq!(pretty(input), expected);
+    }
+
+    #[test]
+    fn test_print_minimal_parentheses() {
+        let tests = [
+            ("(1 + 2) * 3", "(1 + 2) * 3;\n"),
+            ("1 + (2 * 3)", "1 + 2 * 3;\n"),
+            ("(a - b) - c", "a - b - c;\n"),
+            ("a - (b - c)", "a - (b - c);\n"),
+            ("2 ** (3 ** 2)", "2 ** 3 ** 2;\n"),
+            ("(2 ** 3) ** 2", "(2 ** 3) ** 2;\n"),
+            ("(-2) ** 2", "(-2) ** 2;\n"),
+            ("-(2 ** 2)", "-2 ** 2;\n"),
+            ("-(-x)", "--x;\n"),
+            ("!(a && b) || c", "!(a && b) || c;\n"),
+            ("a || (b && c)", "a || b && c;\n"),
+            ("x = (y = 1)", "x = y = 1;\n"),
+            ("(x = 1) + 2", "(x = 1) + 2;\n"),
+            ("(f)(x)[0]", "f(x)[0];\n"),
+            ("(a + b)[0]", "(a + b)[0];\n"),
+            ("(1 << 2) & 3", "1 << 2 & 3;\n"),
+            ("1 << (2 & 3)", "1 << (2 & 3);\n"),
+        ];
+
+        for &test in tests.iter() {
+            assert_eq!(pretty(test.0), test.1, "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_trailing_if_before_grouped_statement() {
+        assert_eq!(
+            pretty("if (a) { 1 }; (b + c) * 2"),
+            "if (a) {\n    1;\n};\n(b + c) * 2;\n"
+        );
+        assert_eq!(pretty("if (a) { 1 }; [b]"), "if (a) {\n    1;\n};\n[b];\n");
+        assert_eq!(pretty("if (a) { 1 }; -1"), "if (a) {\n    1;\n};\n-1;\n");
+        assert_eq!(pretty("if (a) { 1 }; b"), "if (a) {\n    1;\n}\nb;\n");
+    }
+
+    #[test]
+    fn test_print_deep_nesting() {
+        let mut input = "a".to_string();
+        for _ in 0..40 {
+            input = format!("a; fn() {{ {} }}", input);
+        }
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || sender.send(pretty(&input)).unwrap());
+
+        let printed = receiver
+            .recv_timeout(std::time::Duration::from_secs(30))
+            .expect("printer hung or panicked");
+
+        assert_eq!(printed.lines().count(), 1 + 40 * 3);
+    }
+
+    #[test]
+    fn test_print_round_trips() {
+        let corpus = [
+            "let x = 0x1F + 1_000; x",
+            "let s = \"tab\\t\\\"quoted\\\" \\u{1F600}\"; s",
+            "fn(x) { fn(y) { x + y } }(1)(2)",
+            "let m = {}; m[\"a\"] = [1, [2, 3]][1][0];",
+            "if (a) { 1 } else { if (b) { 2 } else { 3 } }",
+            "for (k, v in {1: 2}) { while (false) {} }",
+            "1e-9 * 2.5 - -3 % 4 ^ ~5 | 6 >> 1",
+            "x <= y && y >= z || !w != true",
+            "if (a) { b }; [1, 2][0]",
+            "if (x) { 1 }; -1;",
+            "{\"a\": 1}[\"a\"]",
+        ];
+
+        for input in corpus.iter() {
+            let printed = pretty(input);
+
+            assert_eq!(parse_display(&printed), parse_display(input), "{}", input);
+            assert_eq!(pretty(&printed), printed, "{}", input);
+        }
+    }
+}
@@ -1,26 +1,52 @@
-use crate::{lexer, token};
+use crate::{environment, eval, lexer, parser};
 use std::io;
 use std::io::BufRead;
 
+const PROMPT: &str = ">> ";
+
 pub fn start<R: io::Read, W: io::Write>(io_read: R, io_write: &mut W) {
-    let mut input = String::new();
     let mut buf = std::io::BufReader::new(io_read);
+    let mut env = environment::new();
 
-    io_write.write(">> ".as_bytes()).unwrap();
-    io_write.flush().unwrap();
-
-    buf.read_line(&mut input).unwrap();
+    loop {
+        io_write.write_all(PROMPT.as_bytes()).unwrap();
+        io_write.flush().unwrap();
 
-    let mut lex = lexer::new(input);
+        let mut input = String::new();
+        if buf.read_line(&mut input).unwrap() == 0 {
+            return;
+        }
 
-    loop {
-        let token = lex.next_token();
+        let mut lex = lexer::new(input.clone());
+        let mut parser = parser::new(&mut lex);
+        let program = parser.parse_program();
 
-        if token.token_type == token::EOF {
-            break;
+        if !parser.errors().is_empty() {
+            print_parser_errors(io_write, &input, parser.errors());
+            continue;
         }
 
-        io_write.write(format!("{:?}\n", token).as_bytes()).unwrap();
+        let evaluated = eval::eval_program(&program, &mut env);
+        io_write
+            .write_all(format!("{}\n", evaluated.inspect()).as_bytes())
+            .unwrap();
+    }
+}
+
+fn print_parser_errors<W: io::Write>(io_write: &mut W, input: &str, errors: &[parser::ParseError]) {
+    io_write.write_all(b"parser errors:\n").unwrap();
+
+    for error in errors {
+        io_write
+            .write_all(format!("\t{}\n", error).as_bytes())
+            .unwrap();
+
+        if let Some(line) = input.lines().nth(error.span.line.saturating_sub(1)) {
+            let caret = " ".repeat(error.span.column.saturating_sub(1)) + "^";
+            io_write
+                .write_all(format!("\t{}\n\t{}\n", line, caret).as_bytes())
+                .unwrap();
+        }
     }
 }
 
@@ -30,18 +56,36 @@ mod tests {
 
     #[test]
     fn test_start() {
-        let read = "5 * 5 = 10;".as_bytes();
+        let read = "5 * 5 + 10;\nif (5 > 10) { 1 } else { 2 }\n".as_bytes();
+        let mut output: Vec<u8> = vec![];
+
+        start(read, &mut output);
+
+        let expected = ">> 35\n>> 2\n>> ";
+
+        assert_eq!(expected, std::str::from_utf8(&output).unwrap());
+    }
+
+    #[test]
+    fn test_start_keeps_bindings_across_lines() {
+        let read = "let a = 5;\nlet b = a * 2;\na + b;\n".as_bytes();
+        let mut output: Vec<u8> = vec![];
+
+        start(read, &mut output);
+
+        let expected = ">> null\n>> null\n>> 15\n>> ";
+
+        assert_eq!(expected, std::str::from_utf8(&output).unwrap());
+    }
+
+    #[test]
+    fn test_start_prints_parser_errors() {
+        let read = "let x 5;\n".as_bytes();
         let mut output: Vec<u8> = vec![];
 
         start(read, &mut output);
 
-        let expected = r#">> Token { token_type: "INT", literal: "5" }
-Token { token_type: "*", literal: "*" }
-Token { token_type: "INT", literal: "5" }
-Token { token_type: "=", literal: "=" }
-Token { token_type: "INT", literal: "10" }
-Token { token_type: ";", literal: ";" }
-"#;
+        let expected = ">> parser errors:\n\tline 1, column 7: expected = but got INT\n\tlet x 5;\n\t      ^\n>> ";
 
         assert_eq!(expected, std::str::from_utf8(&output).unwrap());
     }
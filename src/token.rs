@@ -1,77 +1,195 @@
 use lazy_static::lazy_static;
 use std::collections::HashMap;
+use std::fmt;
 
-pub const ILLEGAL: &str = "ILLEGAL";
-pub const EOF: &str = "EOF";
-pub const NULL: &str = "NULL";
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    Illegal,
+    Eof,
+    Null,
 
-// Identifiers + literals
-pub const IDENT: &str = "IDENT";
-pub const INT: &str = "INT";
+    // Identifiers + literals
+    Ident,
+    Int,
+    Float,
+    String,
+    UnterminatedString,
+    Comment,
+    UnterminatedComment,
 
-// Operators
-pub const ASSIGN: &str = "=";
-pub const PLUS: &str = "+";
-pub const MINUS: &str = "-";
-pub const BANG: &str = "!";
-pub const ASTERISK: &str = "*";
-pub const SLASH: &str = "/";
-pub const LT: &str = "<";
-pub const GT: &str = ">";
-pub const EQ: &str = "==";
-pub const NOT_EQ: &str = "!=";
+    // Operators
+    Assign,
+    PlusAssign,
+    MinusAssign,
+    AsteriskAssign,
+    SlashAssign,
+    Plus,
+    Minus,
+    Bang,
+    Asterisk,
+    Slash,
+    Percent,
+    Power,
+    Ampersand,
+    Pipe,
+    Caret,
+    Tilde,
+    ShiftLeft,
+    ShiftRight,
+    Lt,
+    Gt,
+    LtEq,
+    GtEq,
+    Eq,
+    NotEq,
+    And,
+    Or,
 
-// Delimiters
-pub const COMMA: &str = ".";
-pub const SEMICOLON: &str = ";";
-pub const LPAREN: &str = "(";
-pub const RPAREN: &str = ")";
-pub const LBRACE: &str = "{";
-pub const RBRACE: &str = "}";
+    // Delimiters
+    Comma,
+    Semicolon,
+    Colon,
+    LParen,
+    RParen,
+    LBrace,
+    RBrace,
+    LBracket,
+    RBracket,
 
-// Keywords
-pub const FUNCTION: &str = "FUNCTION";
-pub const LET: &str = "LET";
-pub const IF: &str = "IF";
-pub const RETURN: &str = "RETURN";
-pub const ELSE: &str = "ELSE";
-pub const TRUE: &str = "TRUE";
-pub const FALSE: &str = "FALSE";
+    // Keywords
+    Function,
+    Let,
+    If,
+    Return,
+    Else,
+    True,
+    False,
+    While,
+    For,
+    In,
+    Break,
+    Continue,
+}
+
+impl TokenKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TokenKind::Illegal => "ILLEGAL",
+            TokenKind::Eof => "EOF",
+            TokenKind::Null => "NULL",
+            TokenKind::Ident => "IDENT",
+            TokenKind::Int => "INT",
+            TokenKind::Float => "FLOAT",
+            TokenKind::String => "STRING",
+            TokenKind::UnterminatedString => "UNTERMINATED_STRING",
+            TokenKind::Comment => "COMMENT",
+            TokenKind::UnterminatedComment => "UNTERMINATED_COMMENT",
+            TokenKind::Assign => "=",
+            TokenKind::PlusAssign => "+=",
+            TokenKind::MinusAssign => "-=",
+            TokenKind::AsteriskAssign => "*=",
+            TokenKind::SlashAssign => "/=",
+            TokenKind::Plus => "+",
+            TokenKind::Minus => "-",
+            TokenKind::Bang => "!",
+            TokenKind::Asterisk => "*",
+            TokenKind::Slash => "/",
+            TokenKind::Percent => "%",
+            TokenKind::Power => "**",
+            TokenKind::Ampersand => "&",
+            TokenKind::Pipe => "|",
+            TokenKind::Caret => "^",
+            TokenKind::Tilde => "~",
+            TokenKind::ShiftLeft => "<<",
+            TokenKind::ShiftRight => ">>",
+            TokenKind::Lt => "<",
+            TokenKind::Gt => ">",
+            TokenKind::LtEq => "<=",
+            TokenKind::GtEq => ">=",
+            TokenKind::Eq => "==",
+            TokenKind::NotEq => "!=",
+            TokenKind::And => "&&",
+            TokenKind::Or => "||",
+            TokenKind::Comma => ",",
+            TokenKind::Semicolon => ";",
+            TokenKind::Colon => ":",
+            TokenKind::LParen => "(",
+            TokenKind::RParen => ")",
+            TokenKind::LBrace => "{",
+            TokenKind::RBrace => "}",
+            TokenKind::LBracket => "[",
+            TokenKind::RBracket => "]",
+            TokenKind::Function => "FUNCTION",
+            TokenKind::Let => "LET",
+            TokenKind::If => "IF",
+            TokenKind::Return => "RETURN",
+            TokenKind::Else => "ELSE",
+            TokenKind::True => "TRUE",
+            TokenKind::False => "FALSE",
+            TokenKind::While => "WHILE",
+            TokenKind::For => "FOR",
+            TokenKind::In => "IN",
+            TokenKind::Break => "BREAK",
+            TokenKind::Continue => "CONTINUE",
+        }
+    }
+}
 
-pub type Type = String;
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
 
 lazy_static! {
-    static ref KEYWORDS: HashMap<&'static str, &'static str> = [
-        ("fn", FUNCTION),
-        ("let", LET),
-        ("true", TRUE),
-        ("false", FALSE),
-        ("if", IF),
-        ("else", ELSE),
-        ("return", RETURN)
+    static ref KEYWORDS: HashMap<&'static str, TokenKind> = [
+        ("fn", TokenKind::Function),
+        ("let", TokenKind::Let),
+        ("true", TokenKind::True),
+        ("false", TokenKind::False),
+        ("if", TokenKind::If),
+        ("else", TokenKind::Else),
+        ("return", TokenKind::Return),
+        ("while", TokenKind::While),
+        ("for", TokenKind::For),
+        ("in", TokenKind::In),
+        ("break", TokenKind::Break),
+        ("continue", TokenKind::Continue)
     ]
     .iter()
     .cloned()
     .collect();
 }
 
+/// Location of a token in the source: `start`/`end` are byte offsets, `line`
+/// and `column` (both 1-based) point at the first character.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
 #[derive(Debug, Clone)]
 pub struct Token {
-    pub token_type: Type,
+    pub token_type: TokenKind,
     pub literal: String,
+    pub span: Span,
 }
 
-pub fn new(token_type: &str, literal: String) -> Token {
+pub fn new(token_type: TokenKind, literal: String) -> Token {
     Token {
-        token_type: token_type.to_string(),
+        token_type,
         literal,
+        span: Span::default(),
     }
 }
 
-pub fn lookup_identifier(identifier: &str) -> &str {
-    if let Some(v) = KEYWORDS.get(identifier) {
+pub fn lookup_identifier(identifier: &str) -> TokenKind {
+    if let Some(&v) = KEYWORDS.get(identifier) {
         return v;
     }
 
-    IDENT
+    TokenKind::Ident
 }